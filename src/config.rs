@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use clap::Command;
+
+// the options of a --config file are turned into command line arguments and put in front of the real ones
+// every option that is given on the command line is left out of the file so the command line always wins,
+// this also keeps lists like --dimensions from being added to the ones in the file
+pub fn args_with_config(args: Vec<OsString>, command: &Command) -> Vec<OsString> {
+	let path = match config_path(&args) {
+		Some(path) => path,
+		None => return args,
+	};
+	let content = match std::fs::read_to_string(&path) {
+		Ok(content) => content,
+		Err(e) => command.clone().error(clap::error::ErrorKind::Io, format!("failed to read config {}: {}", path.to_string_lossy(), e)).exit(),
+	};
+	match config_args(&content, command, &args[1..]) {
+		Ok(config) => args[..1].iter().cloned().chain(config).chain(args[1..].iter().cloned()).collect(),
+		Err(e) => command.clone().error(clap::error::ErrorKind::InvalidValue, format!("{} in config {}", e, path.to_string_lossy())).exit(),
+	}
+}
+
+fn config_path(args: &[OsString]) -> Option<OsString> {
+	let mut args = args.iter().skip(1);
+	while let Some(arg) = args.next() {
+		let arg = arg.to_string_lossy();
+		if arg == "--" {
+			break;
+		}
+		if arg == "--config" {
+			return args.next().cloned();
+		}
+		if let Some(path) = arg.strip_prefix("--config=") {
+			return Some(path.into());
+		}
+	}
+	None
+}
+
+// keys are the long names of the options, written with - or _ like output-encoding or output_encoding
+// flags are set with true, lists are given as arrays like dimensions = ["overworld", "nether"]
+fn config_args(content: &str, command: &Command, cli_args: &[OsString]) -> Result<Vec<OsString>, String> {
+	let table: toml::Table = content.parse().map_err(|e: toml::de::Error| e.message().to_string())?;
+
+	// every name an option can be given by on the command line
+	let mut ids = HashMap::new();
+	for arg in command.get_arguments() {
+		for name in arg.get_long().into_iter().chain(arg.get_all_aliases().unwrap_or_default()) {
+			ids.insert(format!("--{}", name), arg.get_id().as_str());
+		}
+		if let Some(short) = arg.get_short() {
+			ids.insert(format!("-{}", short), arg.get_id().as_str());
+		}
+	}
+	let mut on_command_line = Vec::new();
+	for arg in cli_args {
+		let arg = arg.to_string_lossy();
+		if arg == "--" {
+			break;
+		}
+		if arg.starts_with("--") {
+			on_command_line.extend(ids.get(arg.split('=').next().unwrap()));
+		} else if let Some(shorts) = arg.strip_prefix('-') {
+			// short flags can be grouped like -vs
+			on_command_line.extend(shorts.chars().filter_map(|short| ids.get(&format!("-{}", short))));
+		}
+	}
+
+	let mut args = Vec::new();
+	for (key, value) in &table {
+		let long = key.replace('_', "-");
+		let arg = command.get_arguments()
+			.find(|arg| arg.get_long() == Some(long.as_str()) && long != "config")
+			.ok_or_else(|| format!("unknown option {}", key))?;
+		if on_command_line.contains(&arg.get_id().as_str()) {
+			continue;
+		}
+		if !arg.get_action().takes_values() {
+			match value {
+				toml::Value::Boolean(true) => args.push(format!("--{}", long).into()),
+				toml::Value::Boolean(false) => {}
+				_ => return Err(format!("{} has to be true or false", key)),
+			}
+			continue;
+		}
+		let values = match value {
+			toml::Value::Array(values) => values.iter().collect(),
+			value => vec![value],
+		};
+		// options that take more than one value at once like --at are given as an array with all of them
+		let together = arg.get_num_args().is_some_and(|num_args| num_args.min_values() > 1);
+		if together {
+			args.push(format!("--{}", long).into());
+		}
+		for value in values {
+			let value = match value {
+				toml::Value::String(value) => value.clone(),
+				toml::Value::Integer(value) => value.to_string(),
+				toml::Value::Float(value) => value.to_string(),
+				_ => return Err(format!("{} has to be a string, a number or an array of them", key)),
+			};
+			if together {
+				args.push(value.into());
+			} else {
+				// with = so values starting with - aren't read as options
+				args.push(format!("--{}={}", long, value).into());
+			}
+		}
+	}
+	Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::{Arg, ArgAction};
+
+	fn command() -> Command {
+		Command::new("test")
+			.arg(Arg::new("format").long("format"))
+			.arg(Arg::new("dimensions").long("dimensions").action(ArgAction::Append).value_delimiter(','))
+			.arg(Arg::new("threads").long("threads"))
+			.arg(Arg::new("at").long("at").num_args(3).allow_negative_numbers(true))
+			.arg(Arg::new("verbose").short('v').long("verbose").action(ArgAction::SetTrue))
+			.arg(Arg::new("pretty").long("pretty").action(ArgAction::SetTrue))
+	}
+
+	#[test]
+	fn command_line_options_override_the_config() {
+		let config = "format = \"json\"\ndimensions = [\"overworld\", \"nether\"]\nthreads = 4\nverbose = true\npretty = false\n";
+		let args = config_args(config, &command(), &[]).unwrap();
+		let mut args: Vec<String> = args.into_iter().map(|arg| arg.into_string().unwrap()).collect();
+		args.sort();
+		assert_eq!(args, vec!["--dimensions=nether", "--dimensions=overworld", "--format=json", "--threads=4", "--verbose"]);
+
+		let cli: Vec<OsString> = vec!["--dimensions".into(), "end".into(), "--threads=2".into(), "-v".into()];
+		let args = config_args(config, &command(), &cli).unwrap();
+		assert_eq!(args, vec![OsString::from("--format=json")]);
+
+		let args = config_args("at = [1, -60, 2]", &command(), &[]).unwrap();
+		assert_eq!(args, vec![OsString::from("--at"), "1".into(), "-60".into(), "2".into()]);
+
+		assert!(config_args("colour = true", &command(), &[]).is_err());
+		assert!(config_args("verbose = 1", &command(), &[]).is_err());
+	}
+}
@@ -0,0 +1,92 @@
+use std::path::Path;
+use flate2::read::GzDecoder;
+use log::warn;
+use crate::types::*;
+
+// the files in the data folder that can hold text written by players or map makers
+// command storage is written by /data modify storage and the scoreboard keeps display names of objectives and teams
+fn is_text_data_file(name: &str) -> bool {
+	name == "scoreboard.dat" || (name.starts_with("command_storage_") && name.ends_with(".dat"))
+}
+
+// collect every string in the command storage and scoreboard files of a world
+pub fn read_data_text(save_path: &Path) -> Vec<DataText> {
+	let data_path = save_path.join("data");
+	let mut names: Vec<String> = match data_path.read_dir() {
+		Ok(files) => files
+			.filter_map(|file| file.ok()?.file_name().into_string().ok())
+			.filter(|name| is_text_data_file(name))
+			.collect(),
+		Err(_e) => {
+			warn!("{} does not exist", data_path.display());
+			return Vec::new();
+		}
+	};
+	names.sort();
+
+	let mut texts = Vec::new();
+	for name in names {
+		let file = match crate::open_shared(&data_path.join(&name)) {
+			Ok(file) => file,
+			Err(e) => {
+				warn!("failed to open {}: {}", name, e);
+				continue;
+			}
+		};
+		let data_file: DataFile = match fastnbt::from_reader(GzDecoder::new(file)) {
+			Ok(data_file) => data_file,
+			Err(e) => {
+				warn!("failed to read {}: {}", name, e);
+				continue;
+			}
+		};
+		collect_strings(&data_file.data, "data", &mut |path, text| texts.push(DataText {
+			file: name.clone(),
+			path,
+			text: text.to_string(),
+		}));
+	}
+	texts
+}
+
+// call found with the dotted path and value of every string in a value
+// compound keys are visited in order so the output is the same every run
+fn collect_strings(value: &fastnbt::Value, path: &str, found: &mut impl FnMut(String, &str)) {
+	match value {
+		fastnbt::Value::String(text) => found(path.to_string(), text),
+		fastnbt::Value::List(values) => {
+			for (index, value) in values.iter().enumerate() {
+				collect_strings(value, &format!("{}[{}]", path, index), found);
+			}
+		}
+		fastnbt::Value::Compound(compound) => {
+			let mut keys: Vec<&String> = compound.keys().collect();
+			keys.sort();
+			for key in keys {
+				collect_strings(&compound[key], &format!("{}.{}", path, key), found);
+			}
+		}
+		_ => {}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashMap;
+
+	#[test]
+	fn strings_are_found_with_their_path() {
+		let storage = fastnbt::Value::Compound(HashMap::from([
+			("contents".to_string(), fastnbt::Value::Compound(HashMap::from([
+				("lore".to_string(), fastnbt::Value::List(vec![fastnbt::Value::String("the old king".to_string())])),
+				("level".to_string(), fastnbt::Value::Int(3)),
+			]))),
+		]));
+		let mut found = Vec::new();
+		collect_strings(&storage, "data", &mut |path, text| found.push((path, text.to_string())));
+		assert_eq!(found, vec![("data.contents.lore[0]".to_string(), "the old king".to_string())]);
+		assert!(is_text_data_file("command_storage_minecraft.dat"));
+		assert!(!is_text_data_file("raids.dat"));
+	}
+}
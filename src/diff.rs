@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::io::Write;
+use crate::types::*;
+use crate::location;
+
+// the same coordinates exist in every dimension so they are part of the position
+type Position = (Dimension, i32, i32, i32);
+
+// an entry that was added, removed or changed at a position
+struct PositionChange<T> {
+	position: Position,
+	change: Change,
+	old: Option<T>,
+	new: Option<T>,
+}
+
+// pair up the entries of both worlds by position and keep the ones that differ
+fn diff_by_position<T: PartialEq>(old: HashMap<Position, T>, mut new: HashMap<Position, T>) -> Vec<PositionChange<T>> {
+	let mut changes = Vec::new();
+	for (position, old_value) in old {
+		match new.remove(&position) {
+			Some(new_value) if new_value == old_value => {}
+			Some(new_value) => changes.push(PositionChange { position, change: Change::Changed, old: Some(old_value), new: Some(new_value) }),
+			None => changes.push(PositionChange { position, change: Change::Removed, old: Some(old_value), new: None }),
+		}
+	}
+	for (position, new_value) in new {
+		changes.push(PositionChange { position, change: Change::Added, old: None, new: Some(new_value) });
+	}
+
+	// sort by x then z like the rest of the output
+	changes.sort_by(|a, b| {
+		let ((a_dimension, ax, ay, az), (b_dimension, bx, by, bz)) = (a.position, b.position);
+		a_dimension.cmp(&b_dimension).then(ax.cmp(&bx)).then(az.cmp(&bz)).then(ay.cmp(&by))
+	});
+	changes
+}
+
+pub fn diff_signs(old: Vec<SignRecord>, new: Vec<SignRecord>) -> Vec<SignChange> {
+	// how a sign was read changes when a world is upgraded so only what is on the sign is compared
+	let by_position = |signs: Vec<SignRecord>| signs.into_iter().map(|sign| ((sign.dimension, sign.x, sign.y, sign.z), SignRecord { provenance: None, ..sign })).collect();
+	diff_by_position(by_position(old), by_position(new)).into_iter()
+		.map(|change| SignChange {
+			change: change.change,
+			dimension: change.position.0,
+			x: change.position.1,
+			y: change.position.2,
+			z: change.position.3,
+			old: change.old,
+			new: change.new,
+		})
+		.collect()
+}
+
+pub fn diff_books(old: Vec<BookRecord>, new: Vec<BookRecord>) -> Vec<BookChange> {
+	let by_position = |books: Vec<BookRecord>| {
+		let mut positions: HashMap<Position, Vec<BookRecord>> = HashMap::new();
+		for book in books {
+			positions.entry((book.dimension, book.x, book.y, book.z)).or_default().push(book);
+		}
+		positions
+	};
+	diff_by_position(by_position(old), by_position(new)).into_iter()
+		.map(|change| BookChange {
+			change: change.change,
+			dimension: change.position.0,
+			x: change.position.1,
+			y: change.position.2,
+			z: change.position.3,
+			old: change.old.unwrap_or_default(),
+			new: change.new.unwrap_or_default(),
+		})
+		.collect()
+}
+
+fn change_name(change: Change) -> &'static str {
+	match change {
+		Change::Added => "added",
+		Change::Removed => "removed",
+		Change::Changed => "changed",
+	}
+}
+
+// write the differences in the human readable text format
+// lines of the old world are prefixed with "old"
+pub fn write_diff_text(file: &mut impl Write, diff: &DiffReport) {
+	for sign in &diff.signs {
+		writeln!(file, "========== {} sign: {} ==========", change_name(sign.change), location(sign.dimension, sign.x, sign.y, sign.z)).unwrap();
+		for line in sign.old.iter().flat_map(|old| &old.lines) {
+			writeln!(file, "old text: {}", line).unwrap();
+		}
+		for line in sign.new.iter().flat_map(|new| &new.lines) {
+			writeln!(file, "text: {}", line).unwrap();
+		}
+		writeln!(file).unwrap();
+	}
+	for books in &diff.books {
+		writeln!(file, "========== {} books: {} ==========", change_name(books.change), location(books.dimension, books.x, books.y, books.z)).unwrap();
+		for book in &books.old {
+			writeln!(file, "old book: {} by {} ({} pages)", book.title.as_deref().unwrap_or_default(), book.author.as_deref().unwrap_or_default(), book.pages.len()).unwrap();
+		}
+		for book in &books.new {
+			writeln!(file, "book: {} by {} ({} pages)", book.title.as_deref().unwrap_or_default(), book.author.as_deref().unwrap_or_default(), book.pages.len()).unwrap();
+		}
+		writeln!(file).unwrap();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::BTreeMap;
+
+	fn sign(x: i32, text: &str) -> SignRecord {
+		SignRecord { dimension: Dimension::Overworld, x, y: 64, z: 0, lines: vec![text.to_string()], back_lines: Vec::new(), color: None, back_color: None, waxed: false, kind: None, rotation: None, facing: None, last_modified: None, raw_text: BTreeMap::new(), provenance: None }
+	}
+
+	#[test]
+	fn signs_are_compared_by_position() {
+		let old = vec![sign(0, "same"), sign(1, "before"), sign(2, "gone")];
+		let new = vec![sign(0, "same"), sign(1, "after"), sign(3, "new")];
+		let changes: Vec<(i32, Change)> = diff_signs(old, new).into_iter().map(|change| (change.x, change.change)).collect();
+		assert_eq!(changes, vec![(1, Change::Changed), (2, Change::Removed), (3, Change::Added)]);
+	}
+}
@@ -0,0 +1,120 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use log::{error, warn};
+
+// output files can be hundreds of megabytes for big worlds, a bigger buffer than the default 8 KiB means fewer writes
+const BUFFER_SIZE: usize = 1 << 16;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum OutputEncoding {
+	/// plain utf-8
+	Utf8,
+	/// utf-8 with a byte order mark for tools that need it to detect utf-8
+	Utf8Bom,
+	/// iso-8859-1, characters that don't fit are replaced with ?
+	Latin1,
+}
+
+// an output file that converts the utf-8 written to it into the chosen encoding
+pub struct EncodedFile {
+	file: BufWriter<File>,
+	encoding: OutputEncoding,
+	// bytes of a character that was split between two writes
+	pending: Vec<u8>,
+	name: String,
+	warned: bool,
+}
+
+impl EncodedFile {
+	pub fn create(path: impl AsRef<Path>, encoding: OutputEncoding) -> io::Result<EncodedFile> {
+		let path = path.as_ref();
+		let mut file = BufWriter::with_capacity(BUFFER_SIZE, File::create(path)?);
+		if encoding == OutputEncoding::Utf8Bom {
+			file.write_all("\u{feff}".as_bytes())?;
+		}
+		Ok(EncodedFile {
+			file,
+			encoding,
+			pending: Vec::new(),
+			name: path.display().to_string(),
+			warned: false,
+		})
+	}
+
+	fn write_latin1(&mut self, text: &str) -> io::Result<()> {
+		let mut encoded = Vec::with_capacity(text.len());
+		for c in text.chars() {
+			match u8::try_from(u32::from(c)) {
+				Ok(byte) => encoded.push(byte),
+				Err(_) => {
+					if !self.warned {
+						warn!("{} contains characters that can't be written as latin1, they were replaced with ?", self.name);
+						self.warned = true;
+					}
+					encoded.push(b'?');
+				}
+			}
+		}
+		self.file.write_all(&encoded)
+	}
+}
+
+impl Write for EncodedFile {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		if self.encoding != OutputEncoding::Latin1 {
+			return self.file.write(buf);
+		}
+
+		self.pending.extend_from_slice(buf);
+		let pending = std::mem::take(&mut self.pending);
+		let valid_up_to = match std::str::from_utf8(&pending) {
+			Ok(_) => pending.len(),
+			// an incomplete character at the end is kept until the rest of it is written
+			Err(e) if e.error_len().is_none() => e.valid_up_to(),
+			Err(_) => return Err(io::Error::new(io::ErrorKind::InvalidData, "output is not valid utf-8")),
+		};
+		let text = std::str::from_utf8(&pending[..valid_up_to]).unwrap();
+		self.write_latin1(text)?;
+		self.pending = pending[valid_up_to..].to_vec();
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.file.flush()
+	}
+}
+
+// BufWriter ignores errors when it writes the rest of the buffer on drop, so a full disk would go unnoticed
+impl Drop for EncodedFile {
+	fn drop(&mut self) {
+		if let Err(e) = self.file.flush() {
+			error!("failed to write {}: {}", self.name, e);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn text_is_written_in_the_chosen_encoding() {
+		let written = |encoding: OutputEncoding| {
+			let path = std::env::temp_dir().join(format!("mc-sign-extractor-test-{:?}.txt", encoding));
+			let mut file = EncodedFile::create(&path, encoding).unwrap();
+			// the euro sign is split between two writes
+			let text = "café €".as_bytes();
+			file.write_all(&text[..7]).unwrap();
+			file.write_all(&text[7..]).unwrap();
+			let warned = file.warned;
+			drop(file);
+			let bytes = std::fs::read(&path).unwrap();
+			std::fs::remove_file(&path).unwrap();
+			(bytes, warned)
+		};
+		assert_eq!(written(OutputEncoding::Utf8), ("café €".as_bytes().to_vec(), false));
+		assert_eq!(written(OutputEncoding::Utf8Bom), ([&[0xef, 0xbb, 0xbf], "café €".as_bytes()].concat(), false));
+		assert_eq!(written(OutputEncoding::Latin1), (b"caf\xe9 ?".to_vec(), true));
+	}
+}
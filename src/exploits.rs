@@ -0,0 +1,82 @@
+use crate::types::*;
+
+// vanilla sign lines are at most a few hundred bytes of json even with formatting on every character
+const MAX_LINE_LENGTH: usize = 2048;
+// vanilla signs nest components one or two levels deep
+const MAX_DEPTH: usize = 16;
+
+// find the lines of a sign that could crash clients, like oversized or deeply nested json
+// old versions store raw text so only signs with json text should be checked
+// the back of a 1.20 sign can be written on the same way so its lines are checked too
+pub fn suspicious_lines(sign: &ChunkLevelTileEntities) -> Vec<SuspiciousLine> {
+	let front = [&sign.text1, &sign.text2, &sign.text3, &sign.text4].into_iter()
+		.enumerate()
+		.filter_map(|(index, line)| Some((false, index + 1, line.clone()?)));
+	let back = sign.back_text.iter()
+		.flat_map(|back_text| back_text.messages.iter().map(crate::sign_message).enumerate())
+		.map(|(index, line)| (true, index + 1, line));
+	front.chain(back)
+		.filter_map(|(back, line_number, line)| {
+			let reason = line_problem(&line)?;
+			Some(SuspiciousLine {
+				dimension: sign.dimension,
+				x: sign.x,
+				y: sign.y,
+				z: sign.z,
+				line: line_number,
+				back,
+				length: line.len(),
+				reason,
+			})
+		})
+		.collect()
+}
+
+// get why a line of json sign text is suspicious
+fn line_problem(line: &str) -> Option<String> {
+	if line.len() > MAX_LINE_LENGTH {
+		return Some(format!("line is {} bytes long", line.len()));
+	}
+	// empty lines are left out by some versions instead of being written as json
+	if line.is_empty() {
+		return None;
+	}
+	let value: serde_json::Value = match serde_json::from_str(line) {
+		Ok(value) => value,
+		Err(e) => return Some(format!("line is not valid json: {}", e)),
+	};
+	let depth = json_depth(&value);
+	if depth > MAX_DEPTH {
+		return Some(format!("json is nested {} levels deep", depth));
+	}
+	None
+}
+
+// count how deep objects and arrays are nested in a json value
+fn json_depth(value: &serde_json::Value) -> usize {
+	match value {
+		serde_json::Value::Array(values) => 1 + values.iter().map(json_depth).max().unwrap_or(0),
+		serde_json::Value::Object(object) => 1 + object.values().map(json_depth).max().unwrap_or(0),
+		_ => 0,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn normal_lines_are_not_suspicious() {
+		assert_eq!(line_problem(r#"{"text":"a","extra":[{"text":"b","color":"red"}]}"#), None);
+		assert_eq!(line_problem(r#""plain""#), None);
+		assert_eq!(line_problem(""), None);
+	}
+
+	#[test]
+	fn broken_long_and_nested_lines_are_suspicious() {
+		assert!(line_problem(r#"{"text":"#).is_some());
+		assert!(line_problem(&format!(r#"{{"text":"{}"}}"#, "a".repeat(MAX_LINE_LENGTH))).is_some());
+		let nested = format!("{}{}", r#"{"extra":["#.repeat(MAX_DEPTH), "]}".repeat(MAX_DEPTH));
+		assert!(line_problem(&nested).is_some());
+	}
+}
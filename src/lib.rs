@@ -0,0 +1,4 @@
+// helpers that can be used without running the extractor, the binary in main.rs uses them as well
+
+// import sign and book text reconstruction from text.rs
+pub mod text;
@@ -1,518 +1,4272 @@
 use std::path::{Path, PathBuf};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use regex::Regex;
 use std::fs::File;
+use std::sync::OnceLock;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::io::prelude::*;
 use flate2::read::{ZlibDecoder, GzDecoder};
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use log::{debug, error, info, warn};
+
+// import sign text reconstruction from the library in lib.rs
+use mc_sign_extractor::text::*;
 
 // import types from types.rs
 mod types;
 use crate::types::*;
 
-#[derive(Parser,Debug)]
-#[command(author, version, about, long_about)]
-struct Opts {
-	/// minecraft save folder
-	#[clap(short, long)]
-	save: String,
-}
+// import version thresholds from version.rs
+mod version;
+use crate::version::*;
 
+// import sign orientation lookup from orientation.rs
+mod orientation;
+use crate::orientation::*;
 
-fn main() {
-	let opts: Opts = Opts::parse();
+// import world comparison from diff.rs
+mod diff;
+use crate::diff::*;
 
-	// check if save folder exists
-	let save_path = Path::new(&opts.save);
-	if !save_path.exists() {
-		println!("save folder does not exist");
-		return;
-	}
-	let save_name = save_path.file_name().unwrap().to_str().unwrap();
+// import coordinate detection from waypoints.rs
+mod waypoints;
+use crate::waypoints::*;
 
-	// check if save folder is a directory
-	if !save_path.is_dir() {
-		println!("save folder is not a directory");
-		return;
-	}
+// import output encodings from encoding.rs
+mod encoding;
+use crate::encoding::*;
 
-	// get save version
-	let version_path = save_path.join("level.dat");
-	if !version_path.exists() {
-		println!("save version does not exist");
-		return;
-	}
-	let version_file = File::open(version_path).expect("failed to open file");
-	//println!("{:#?}",nbt::Blob::from_reader(&mut GzDecoder::new(version_file)).expect("failed to read nbt"));
-	//return;
-	let version_nbt: LevelDat = fastnbt::from_reader(GzDecoder::new(version_file)).expect("failed to read nbt");
+// import exploit detection from exploits.rs
+mod exploits;
+use crate::exploits::*;
 
-	// if Version is None then we are using an old version of minecraft
-	// fallback to old version
-	let version = match version_nbt.data.version {
-		Some(version) => version,
-		None => {
-			LevelDatDataVersion {
-				id: version_nbt.data.old_version,
-				name: "old".to_string(),
-				snapshot: false
-			}
-		}
-	};
+// import phase timing from profile.rs
+mod profile;
+use crate::profile::*;
 
-	// print version
-	println!("world_version: {} id: {}", version.name, version.id);
+// import database output from sqlite.rs
+mod sqlite;
+use crate::sqlite::*;
 
+// import command storage and scoreboard reading from data.rs
+mod data;
+use crate::data::*;
 
-	// get all files in region folder
-	let region_path = save_path.join("region");
-	let region_files = region_path.read_dir().unwrap();
-	let mut signs:Vec<ChunkLevelTileEntities> = Vec::new();
+// import player inventory reading from playerdata.rs
+mod playerdata;
+use crate::playerdata::*;
 
-	// get number of threads
-	let num_threads = num_cpus::get();
-	// switch to 1 thread for testing
-	//let num_threads = 1;
+// import sign line format checks from validate.rs
+mod validate;
+use crate::validate::*;
 
-	// create thread pool
-	let pool = threadpool::Builder::new().num_threads(num_threads).build();
+// import the read ahead limit of the reader threads from pipeline.rs
+mod pipeline;
+use crate::pipeline::*;
 
-	// create a channel to send the signs from the threads
-	let (tx, rx) = std::sync::mpsc::channel();
-	let (tx_books, rx_books) = std::sync::mpsc::channel();
+// import watching a world for saved regions from watch.rs
+mod watch;
+use crate::watch::*;
 
-	let mut number_of_files = 0;
-	for file in region_files {
-		let file = file.unwrap();
-		let file_path = file.path();
-
-		// clone the sender
-		let thread_tx = tx.clone();
-		let thread_tx_books = tx_books.clone();
-		let thread_version = version.clone();
-		pool.execute(move || {
-			// extract signs from mca file
-			let (signs,books) = extract_signs_from_mca(file_path, thread_version);
-			thread_tx.send(signs).unwrap();
-			thread_tx_books.send(books).unwrap();
-		});
-		number_of_files += 1;
-	}
-	pool.join();
+// import reading options from a toml file from config.rs
+mod config;
+use crate::config::*;
 
-	// collect all the results from the threads
-	rx.iter().take(number_of_files).for_each(|signs_from_thread| {
-		signs.extend(signs_from_thread);
-	});
+// set when ctrl-c is pressed, regions that weren't started yet are skipped so what was found can still be written
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
 
-	// sort signs by x then z
-	signs.sort_by(|a, b| {
-		a.x.cmp(&b.x).then(a.z.cmp(&b.z)).then(a.y.cmp(&b.y))
-	});
-	
-	// collect all the books from the threads
-	let mut books:Vec<BookWithPos> = Vec::new();
-	rx_books.iter().take(number_of_files).for_each(|books_from_thread| {
-		books.extend(books_from_thread);
-	});
+fn interrupted() -> bool {
+	INTERRUPTED.load(Ordering::Relaxed)
+}
 
-	// sort books by x then z
-	books.sort_by(|a, b| {
-		a.x.cmp(&b.x).then(a.z.cmp(&b.z)).then(a.y.cmp(&b.y))
-	});
+// signs and books found by all threads so far, only counted with --limit
+static FOUND_SIGNS: AtomicUsize = AtomicUsize::new(0);
+static FOUND_BOOKS: AtomicUsize = AtomicUsize::new(0);
+// set once --limit signs or books were found, the remaining regions are skipped the same way as after ctrl-c
+static LIMIT_REACHED: AtomicBool = AtomicBool::new(false);
 
-	// if version is old then the text is raw but if it is newer then it is json
-	// the json is in the format {"text":"text"} with an optional "extra" field
-	// that contains an array of more json objects
-	
-	// write signs to file
-	let mut file = File::create(format!("signs-{save_name}.txt")).unwrap();
+// every save is limited on its own
+fn reset_limit() {
+	FOUND_SIGNS.store(0, Ordering::Relaxed);
+	FOUND_BOOKS.store(0, Ordering::Relaxed);
+	LIMIT_REACHED.store(false, Ordering::Relaxed);
+}
 
-	for sign in signs {
-		writeln!(file, "========== sign location: {},{},{} ==========", sign.x, sign.y, sign.z).unwrap();
-
-		// print text all text fields
-		// all text fields exist since we only extract signs
-		if version.name != "old".to_owned() {
-			// convert sign text from json to struct
-			let sign_text_1: SignText = serde_json::from_str(&sign.text1.unwrap()).unwrap();
-			
-			// if extra exists then combine all the text fields
-			if let Some(extra) = sign_text_1.extra {
-				let mut text = sign_text_1.text;
-				for extra in extra {
-					text.push_str(&extra.text);
-				}
-				writeln!(file, "text: {}", text).unwrap();
-			} else {
-				writeln!(file, "text: {}", sign_text_1.text).unwrap();
-			}
+// add what a region contained to the counts for --limit
+fn count_found(result: &RegionResult, limit: Option<usize>) {
+	if let Some(limit) = limit {
+		let signs = FOUND_SIGNS.fetch_add(result.signs.len(), Ordering::Relaxed) + result.signs.len();
+		let books = FOUND_BOOKS.fetch_add(result.books.len(), Ordering::Relaxed) + result.books.len();
+		if signs >= limit || books >= limit {
+			LIMIT_REACHED.store(true, Ordering::Relaxed);
+		}
+	}
+}
 
-			// repeat for all text fields
-			
-			let sign_text_2: SignText = serde_json::from_str(&sign.text2.unwrap()).unwrap();
-			if let Some(extra) = sign_text_2.extra {
-				let mut text = sign_text_2.text;
-				for extra in extra {
-					text.push_str(&extra.text);
-				}
-				writeln!(file, "text: {}", text).unwrap();
-			} else {
-				writeln!(file, "text: {}", sign_text_2.text).unwrap();
-			}
+// drop the signs and books outside of --at, --min-y and --max-y and the signs without the text from --include-text and --exclude-text
+// in the thread that read them so --limit only counts the ones that end up in the output
+fn keep_wanted(result: &mut RegionResult, opts: &Opts, version: &LevelDatDataVersion) {
+	result.signs.retain(|sign| opts.position_wanted(sign.x, sign.y, sign.z) && opts.sign_text_wanted(sign, version));
+	result.books.retain(|book| opts.position_wanted(book.x, book.y, book.z));
+	result.raw_signs.retain(|sign| opts.position_wanted(sign.x, sign.y, sign.z));
+}
 
-			let sign_text_3: SignText = serde_json::from_str(&sign.text3.unwrap()).unwrap();
-			if let Some(extra) = sign_text_3.extra {
-				let mut text = sign_text_3.text;
-				for extra in extra {
-					text.push_str(&extra.text);
-				}
-				writeln!(file, "text: {}", text).unwrap();
-			} else {
-				writeln!(file, "text: {}", sign_text_3.text).unwrap();
-			}
+// whether regions that weren't started yet should be skipped
+fn stop_scanning() -> bool {
+	interrupted() || LIMIT_REACHED.load(Ordering::Relaxed)
+}
 
-			let sign_text_4: SignText = serde_json::from_str(&sign.text4.unwrap()).unwrap();
-			if let Some(extra) = sign_text_4.extra {
-				let mut text = sign_text_4.text;
-				for extra in extra {
-					text.push_str(&extra.text);
-				}
-				writeln!(file, "text: {}", text).unwrap();
-			} else {
-				writeln!(file, "text: {}", sign_text_4.text).unwrap();
+#[derive(Parser,Debug,Clone)]
+#[command(author, version, about, long_about)]
+struct Opts {
+	/// read options from a toml file with their long names as keys, like format = "json" or dimensions = ["overworld", "nether"]
+	/// flags are set with true, options given on the command line override the ones in the file
+	#[clap(long)]
+	config: Option<PathBuf>,
+	/// minecraft save folder, can be given more than once to read the saves one after the other
+	/// files given to --manifest, --density, --sqlite and other options get the name of the save added when there is more than one
+	#[clap(short, long, required_unless_present_any = ["stdin", "list_versions"])]
+	save: Vec<String>,
+	/// read a single region file from stdin instead of a save folder, the version has to be given with --force-version
+	#[clap(long, requires = "force_version", conflicts_with_all = ["save", "cubic_chunks", "chunks", "at", "spawn_only", "diff", "follow_poi", "data_text", "player_books"])]
+	stdin: bool,
+	/// also extract command block commands and sign click commands
+	#[clap(long)]
+	commands: bool,
+	/// include urls, commands and tooltips from sign click/hover events in the output
+	#[clap(long)]
+	events: bool,
+	/// only scan region files and chunks modified since a unix timestamp or a duration ago (e.g. 30m, 12h, 7d)
+	#[clap(long, value_parser = parse_modified_since)]
+	modified_since: Option<SystemTime>,
+	/// output format
+	#[clap(long, value_enum, default_value_t = Format::Text)]
+	format: Format,
+	/// indent json output
+	#[clap(long)]
+	pretty: bool,
+	/// text encoding of the output files
+	#[clap(long, value_enum, default_value_t = OutputEncoding::Utf8)]
+	output_encoding: OutputEncoding,
+	/// where to extract books from
+	#[clap(long, value_enum, value_delimiter = ',', default_values_t = [BookSource::Container, BookSource::Lectern, BookSource::Entity, BookSource::Frame])]
+	book_sources: Vec<BookSource>,
+	/// dotted path from an item to the pages of a modded book, e.g. tag.book.pages
+	#[clap(long, value_parser = parse_book_tag_path)]
+	book_tag_path: Option<String>,
+	/// write the number of signs and books per chunk to a csv file
+	#[clap(long)]
+	density: Option<PathBuf>,
+	/// write the raw nbt of every sign to a file for debugging
+	#[clap(long)]
+	dump_raw_signs: Option<PathBuf>,
+	/// dimensions to read, the nether and the end are stored in the DIM-1 and DIM1 folders
+	#[clap(long, value_enum, value_delimiter = ',', default_values_t = [Dimension::Overworld])]
+	dimensions: Vec<Dimension>,
+	/// use this data version instead of the one in level.dat to pick the chunk format, 0 for worlds from before data versions
+	#[clap(long, alias = "assume-version")]
+	force_version: Option<i32>,
+	/// read every chunk with the first chunk format that fits instead of the one of the world version, for broken or heavily modded worlds
+	#[clap(long)]
+	ignore_version: bool,
+	/// order of the signs and books in every dimension
+	#[clap(long, value_enum, default_value_t = SortBy::X)]
+	sort_by: SortBy,
+	/// put the signs and books of the text output under a header for every dimension or region file
+	#[clap(long, value_enum)]
+	group_by: Option<GroupBy>,
+	/// read a world saved by the cubic chunks mod (region3d/*.3dr) instead of the vanilla region folder
+	#[clap(long)]
+	cubic_chunks: bool,
+	/// write a csv of every region file that was read with its size, number of chunks and how many of them couldn't be read
+	#[clap(long)]
+	manifest: Option<PathBuf>,
+	/// write every region, chunk and sign orientation that couldn't be read to a file, given as json:<file>
+	#[clap(long, value_parser = parse_error_report)]
+	error_report: Option<PathBuf>,
+	/// only read these chunks, given as chunk coordinates like "0,0 1,0 -3,5"
+	#[clap(long, value_delimiter = ' ', value_parser = parse_chunk_position, allow_hyphen_values = true, conflicts_with = "cubic_chunks")]
+	chunks: Vec<(i32, i32)>,
+	/// only read the chunk of the block at these coordinates, like the ones on the f3 screen, and write the sign or book at the block
+	#[clap(long, num_args = 3, value_names = ["X", "Y", "Z"], allow_negative_numbers = true, conflicts_with_all = ["chunks", "cubic_chunks"])]
+	at: Option<Vec<i32>>,
+	/// only read the chunks within this many chunks of the spawn point in level.dat
+	#[clap(long, conflicts_with = "cubic_chunks")]
+	spawn_only: Option<i32>,
+	/// write the signs and books of every region to their own files in this directory on all threads instead of one file
+	#[clap(long)]
+	shards: Option<PathBuf>,
+	/// keep running after the output is written and append the signs and books that change whenever the world is saved to watch-{save}.txt
+	/// or watch-{save}.ndjson, regions are read again when their files change so this can follow a running server
+	#[clap(long, conflicts_with_all = ["stdin", "diff", "validate", "cubic_chunks", "limit", "shards"])]
+	watch: bool,
+	/// number of threads parsing region files, defaults to the number of cpus
+	#[clap(long)]
+	threads: Option<usize>,
+	/// number of threads reading region files from disk and handing them to the parsing threads
+	/// a hard drive is usually fastest with 1 or 2, an ssd with about as many as --threads
+	/// without it every thread reads the region file it parses itself
+	#[clap(long, conflicts_with_all = ["stdin", "cubic_chunks"])]
+	threads_io: Option<usize>,
+	/// stop reading regions once this many signs or books were found, for a quick look at a big world
+	#[clap(long, conflicts_with = "diff")]
+	limit: Option<usize>,
+	/// print the number of books, different authors, different titles and pages at the end
+	#[clap(long)]
+	book_stats: bool,
+	/// write books with the same title, author and text once with a list of where the copies are
+	#[clap(long)]
+	dedup_books: bool,
+	/// write signs and books to a single report file instead of separate files
+	#[clap(long)]
+	combined: bool,
+	/// write sign lines as "text:" like older versions instead of numbering them as "line1:" to "line4:", for scripts reading the text output
+	#[clap(long)]
+	legacy_format: bool,
+	/// write every sign on a single line like "x y z | line1 | line2 | line3 | line4" in the text format, followed by "| back: line1 | ..." when the back of a 1.20 sign is written on
+	#[clap(long)]
+	flat: bool,
+	/// keep formatting codes like §l in book pages and sign text instead of removing them
+	#[clap(long)]
+	keep_formatting: bool,
+	/// write the json text components of sign lines as they are stored instead of their plain text, old raw text isn't changed
+	#[clap(long)]
+	raw_json: bool,
+	/// write the pages of every book in the text output as one block of text without the page headers, for search indexing
+	#[clap(long)]
+	flatten_books: bool,
+	/// write one json object per line (ndjson) instead of a json array, for bulk importing into search engines
+	#[clap(long, conflicts_with = "combined")]
+	json_per_line: bool,
+	/// also list villager workstations from the poi folder
+	#[clap(long)]
+	follow_poi: bool,
+	/// compare with an older copy of the world and only write the signs and books that were added, removed or changed
+	#[clap(long)]
+	diff: Option<String>,
+	/// also write the signs that have coordinates on them and the coordinates they point to
+	#[clap(long)]
+	waypoints: bool,
+	/// only keep signs whose text matches this regex
+	#[clap(long, value_parser = parse_text_pattern)]
+	include_text: Option<Regex>,
+	/// drop signs whose text matches this regex, like shop signs with "\[Buy\]", applied after --include-text
+	#[clap(long, value_parser = parse_text_pattern)]
+	exclude_text: Option<Regex>,
+	/// only keep signs and books at or above this y coordinate, can be negative in 1.18+ worlds
+	#[clap(long, allow_negative_numbers = true)]
+	min_y: Option<i32>,
+	/// only keep signs and books at or below this y coordinate
+	#[clap(long, allow_negative_numbers = true)]
+	max_y: Option<i32>,
+	/// also write all signs and books to a sqlite database with full text search tables, replacing the file if it exists
+	#[clap(long)]
+	sqlite: Option<PathBuf>,
+	/// also write the names, targets and metadata of jigsaw and structure blocks
+	#[clap(long)]
+	structures: bool,
+	/// also write every container with its items, their custom names, lore and enchantments, and the music discs in jukeboxes
+	#[clap(long)]
+	containers: bool,
+	/// also write the text in command storage and scoreboard files of the data folder
+	#[clap(long)]
+	data_text: bool,
+	/// also write the books in the inventories and ender chests of players from the playerdata folder
+	/// with the names of the players from the usercache.json of the server
+	#[clap(long)]
+	player_books: bool,
+	/// add the unix time the chunk of every sign and book was last saved, not available for cubic chunks
+	#[clap(long, conflicts_with = "diff")]
+	timestamps: bool,
+	/// also write the signs with broken, oversized or deeply nested json that can crash clients
+	#[clap(long)]
+	detect_exploits: bool,
+	/// write how every sign line can be read (json, raw text or broken json) to validate-{save} instead of the normal output, to check the version guess
+	#[clap(long)]
+	validate: bool,
+	/// count the block entities of every id that were found and print them at the end, to find modded ones
+	#[clap(long)]
+	list_block_entities: bool,
+	/// also write the seed and spawn point of the world to world-info-{save}.txt
+	#[clap(long)]
+	world_info: bool,
+	/// log every region file that is read instead of only the ones with signs or books
+	#[clap(short, long)]
+	verbose: bool,
+	/// read a world that a running server is saving, chunks that fail to read because they are being written are read again a few times
+	#[clap(long)]
+	live: bool,
+	/// print the ranges of data versions that are read differently and exit
+	#[clap(long)]
+	list_versions: bool,
+	/// print how much time was spent reading, decompressing, parsing and writing at the end
+	#[clap(long)]
+	profile: bool,
+}
+
+// the options the extraction threads need, cloned into every thread
+#[derive(Clone, Debug)]
+struct ExtractOptions {
+	book_sources: Vec<BookSource>,
+	book_tag_path: Option<String>,
+	dump_raw_signs: bool,
+	// the dimension of the region file a thread is reading
+	dimension: Dimension,
+	// the chunks to read, all chunks are read if this is empty
+	chunks: Vec<(i32, i32)>,
+	// the chunk x and z of the spawn point and the radius in chunks from --spawn-only
+	spawn_area: Option<(i32, i32, i32)>,
+	structures: bool,
+	containers: bool,
+	timestamps: bool,
+	// --modified-since as unix time, compared with the time every chunk was saved
+	modified_since: Option<u32>,
+	ignore_version: bool,
+	list_block_entities: bool,
+	live: bool,
+	// the region file is from the entities folder of 1.17 and later which only holds entities
+	entities: bool,
+}
+
+impl ExtractOptions {
+	fn chunk_wanted(&self, chunk_x: i32, chunk_z: i32) -> bool {
+		let in_spawn_area = match self.spawn_area {
+			Some((spawn_x, spawn_z, radius)) => (chunk_x - spawn_x).abs() <= radius && (chunk_z - spawn_z).abs() <= radius,
+			None => true,
+		};
+		in_spawn_area && (self.chunks.is_empty() || self.chunks.contains(&(chunk_x, chunk_z)))
+	}
+
+	// regions hold 32x32 chunks
+	fn region_wanted(&self, rx: i32, rz: i32) -> bool {
+		let in_spawn_area = match self.spawn_area {
+			Some((spawn_x, spawn_z, radius)) => {
+				let overlaps = |region: i32, spawn: i32| region * 32 <= spawn + radius && region * 32 + 31 >= spawn - radius;
+				overlaps(rx, spawn_x) && overlaps(rz, spawn_z)
 			}
+			None => true,
+		};
+		in_spawn_area && (self.chunks.is_empty() || self.chunks.iter().any(|(chunk_x, chunk_z)| chunk_x.div_euclid(32) == rx && chunk_z.div_euclid(32) == rz))
+	}
+}
 
-		} else {
-			// if version is old then the text is raw
-			writeln!(file, "text: {}", sign.text1.unwrap()).unwrap();
-			writeln!(file, "text: {}", sign.text2.unwrap()).unwrap();
-			writeln!(file, "text: {}", sign.text3.unwrap()).unwrap();
-			writeln!(file, "text: {}", sign.text4.unwrap()).unwrap();
+impl Opts {
+	// check a position against --at and the y coordinate against --min-y and --max-y, both are inclusive
+	fn position_wanted(&self, x: i32, y: i32, z: i32) -> bool {
+		self.at().is_none_or(|at| at == (x, y, z))
+			&& self.min_y.is_none_or(|min_y| y >= min_y) && self.max_y.is_none_or(|max_y| y <= max_y)
+	}
+
+	// with more than one save every save gets its own file, named like manifest-{save}.csv
+	fn output_path(&self, path: &Path, save_name: &str) -> PathBuf {
+		if self.save.len() <= 1 {
+			return path.to_path_buf();
+		}
+		let stem = path.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+		match path.extension() {
+			Some(extension) => path.with_file_name(format!("{}-{}.{}", stem, save_name, extension.to_string_lossy())),
+			None => path.with_file_name(format!("{}-{}", stem, save_name)),
 		}
-		writeln!(file, "").unwrap();
 	}
 
-	// write all books to a file
-	let mut file = File::create(format!("books-{save_name}.txt")).unwrap();
-	
+	// the block from --at, clap makes sure it has three coordinates
+	fn at(&self) -> Option<(i32, i32, i32)> {
+		self.at.as_ref().map(|at| (at[0], at[1], at[2]))
+	}
 
-	for book in books {
-		// write xyz coordinates
-		writeln!(file, "=========== book location: {},{},{} ==========", book.x, book.y, book.z).unwrap();
+	// check the text of a sign against --include-text and then --exclude-text
+	// the lines are joined with newlines so a pattern can span more than one line
+	fn sign_text_wanted(&self, sign: &ChunkLevelTileEntities, version: &LevelDatDataVersion) -> bool {
+		if self.include_text.is_none() && self.exclude_text.is_none() {
+			return true;
+		}
+		// the filters look at the plain text even with --raw-json
+		let record = sign_record(sign, version, LineOptions { raw_json: false, ..self.line_options() });
+		let text = record.lines.iter().chain(&record.back_lines).map(String::as_str).collect::<Vec<_>>().join("\n");
+		self.include_text.as_ref().is_none_or(|include| include.is_match(&text))
+			&& !self.exclude_text.as_ref().is_some_and(|exclude| exclude.is_match(&text))
+	}
 
-		let book = book.book;
-		// print book title, author and text
-		// check if book has title (writable books don't have titles and author)
-		if let Some(title) = book.title {
-			writeln!(file, "title: {}", title).unwrap();
-		} else {
-			writeln!(file, "title: unknown").unwrap();
+	fn line_options(&self) -> LineOptions {
+		LineOptions {
+			keep_formatting: self.keep_formatting,
+			raw_json: self.raw_json,
 		}
-		// check if book has author
-		if let Some(author) = book.author {
-			writeln!(file, "author: {}", author).unwrap();
-		} else {
-			writeln!(file, "author: unknown").unwrap();
+	}
+
+	// the threads parsing region files and writing shards
+	fn threads(&self) -> usize {
+		self.threads.unwrap_or_else(num_cpus::get).max(1)
+	}
+
+	fn extract_options(&self) -> ExtractOptions {
+		ExtractOptions {
+			book_sources: self.book_sources.clone(),
+			book_tag_path: self.book_tag_path.clone(),
+			dump_raw_signs: self.dump_raw_signs.is_some(),
+			dimension: Dimension::Overworld,
+			// only the chunk of the block from --at is read
+			chunks: match self.at() {
+				Some((x, _, z)) => vec![(x.div_euclid(16), z.div_euclid(16))],
+				None => self.chunks.clone(),
+			},
+			spawn_area: None,
+			structures: self.structures,
+			containers: self.containers,
+			timestamps: self.timestamps,
+			modified_since: self.modified_since.map(|since| since.duration_since(UNIX_EPOCH).map(|since| since.as_secs() as u32).unwrap_or(0)),
+			ignore_version: self.ignore_version,
+			list_block_entities: self.list_block_entities,
+			live: self.live,
+			entities: false,
 		}
-		let pages = book.pages.unwrap();
+	}
+}
 
-		writeln!(file, "{}", format!("pages: {}", pages.len()) ).unwrap();
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum BookSource {
+	/// chests, barrels, shulker boxes and other containers
+	Container,
+	/// books placed on lecterns
+	Lectern,
+	/// dropped books lying on the ground
+	Entity,
+	/// books in item frames
+	Frame,
+}
 
-		let mut page_number = 1;
-		// iterate over all pages
-		for page in pages {
-			writeln!(file, "---------- page {} ----------", page_number).unwrap();
-			// print page text
-			// replace the following formatting codes with nothing so they don't appear in the text
-			// and the lowercase versions of the formatting codes with nothing so they don't appear in the text
-			/* 
-				§ + k creates randomly changing characters.
-				§ + l creates bold text.
-				§ + m creates strikethrough text.
-				§ + n creates underlined text.
-				§ + o creates italic text.
-				§ + 0 – f (hexadecimal) creates colored text.
-				§ + r resets any of the previous styles so text after it appears normally.
-			*/
-			let page = page.replace("§k", "")
-				.replace("§l", "")
-				.replace("§L", "")
-				.replace("§m", "")
-				.replace("§M", "")
-				.replace("§n", "")
-				.replace("§N", "")
-				.replace("§o", "")
-				.replace("§O", "")
-				.replace("§r", "")
-				.replace("§R", "")
-				.replace("§a", "")
-				.replace("§A", "")
-				.replace("§b", "")
-				.replace("§B", "")
-				.replace("§c", "")
-				.replace("§C", "")
-				.replace("§d", "")
-				.replace("§D", "")
-				.replace("§e", "")
-				.replace("§E", "")
-				.replace("§f", "")
-				.replace("§F", "")
-				.replace("§K", "")
-				.replace("§0", "")
-				.replace("§1", "")
-				.replace("§2", "")
-				.replace("§3", "")
-				.replace("§4", "")
-				.replace("§5", "")
-				.replace("§6", "")
-				.replace("§7", "")
-				.replace("§8", "")
-				.replace("§9", "");
-			
-			// replace § with nothing so it doesn't appear in the text
-			let page = page.replace("§", "");
-			// write page text to file
-			writeln!(file, "{}", page).unwrap();
-			page_number += 1;
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum SortBy {
+	/// x then z then y
+	X,
+	/// z then x then y
+	Z,
+	/// y then x then z, one layer after the other
+	Y,
+	/// horizontal distance from 0,0, closest first
+	Dist,
+}
+
+impl SortBy {
+	// compare two x, y, z positions
+	fn compare(self, a: (i32, i32, i32), b: (i32, i32, i32)) -> std::cmp::Ordering {
+		let (ax, ay, az) = a;
+		let (bx, by, bz) = b;
+		match self {
+			SortBy::X => ax.cmp(&bx).then(az.cmp(&bz)).then(ay.cmp(&by)),
+			SortBy::Z => az.cmp(&bz).then(ax.cmp(&bx)).then(ay.cmp(&by)),
+			SortBy::Y => ay.cmp(&by).then(ax.cmp(&bx)).then(az.cmp(&bz)),
+			SortBy::Dist => {
+				// squared distances fit in an i64 and sort the same way
+				let distance = |x: i32, z: i32| i64::from(x) * i64::from(x) + i64::from(z) * i64::from(z);
+				distance(ax, az).cmp(&distance(bx, bz)).then(SortBy::X.compare(a, b))
+			}
 		}
-		writeln!(file, "").unwrap();
-	}	
-    eprintln!("done!");
+	}
 }
 
-fn extract_signs_from_mca(file_path:PathBuf, version:LevelDatDataVersion) -> (Vec<ChunkLevelTileEntities>, Vec<BookWithPos>) {
-	let mut signs:Vec<ChunkLevelTileEntities> = Vec::new();
-	let mut books:Vec<BookWithPos> = Vec::new();
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum GroupBy {
+	/// a header for every dimension
+	Dimension,
+	/// a header for every dimension and every region file in it
+	Region,
+}
 
-	let file_name = file_path.file_name().unwrap().to_str().unwrap();
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq)]
+enum Format {
+	/// human readable text
+	Text,
+	/// json array of records
+	Json,
+	/// geojson points for gis tools with x and z as coordinates, other outputs are written as json
+	Geojson,
+	/// markdown documents for publishing books on a wiki, other outputs are written as text
+	Markdown,
+	/// a datapack function with a setblock command for every sign to place them again in 1.20 to 1.21.4, books and other outputs are written as text
+	Mcfunction,
+}
 
-	// check if file name matches regex
-	let re: Regex = Regex::new(r"r\.(?P<rx>-?\d+)\.(?P<ry>-?\d+)\.mca").expect("invalid regex");
-	let caps = match re.captures(file_name){
-		Some(caps) => caps,
-		None => return (signs,books),
+// item paths start at the tag since that is the only part of an item that can hold pages
+fn parse_book_tag_path(arg: &str) -> Result<String, String> {
+	match arg.strip_prefix("tag.") {
+		Some(rest) if !rest.is_empty() && !rest.split('.').any(str::is_empty) => Ok(arg.to_string()),
+		_ => Err(format!("invalid book tag path: {arg}, expected a dotted path starting with tag, e.g. tag.book.pages")),
+	}
+}
+
+fn parse_text_pattern(arg: &str) -> Result<Regex, String> {
+	Regex::new(arg).map_err(|e| e.to_string())
+}
+
+// parse chunk coordinates like -3,5
+fn parse_chunk_position(arg: &str) -> Result<(i32, i32), String> {
+	let (x, z) = arg.split_once(',').ok_or(format!("expected chunk coordinates like 0,0 but got {arg}"))?;
+	let x = x.trim().parse::<i32>().map_err(|_| format!("invalid chunk x coordinate in {arg}"))?;
+	let z = z.trim().parse::<i32>().map_err(|_| format!("invalid chunk z coordinate in {arg}"))?;
+	Ok((x, z))
+}
+
+// parse the file of --error-report, json is the only format but the prefix keeps room for others
+fn parse_error_report(arg: &str) -> Result<PathBuf, String> {
+	match arg.split_once(':') {
+		Some(("json", path)) if !path.is_empty() => Ok(PathBuf::from(path)),
+		_ => Err(format!("expected json:<file> but got {arg}")),
+	}
+}
+
+// parse either a unix timestamp or a duration with a unit suffix (s, m, h, d, w)
+fn parse_modified_since(arg: &str) -> Result<SystemTime, String> {
+	if let Ok(timestamp) = arg.parse::<u64>() {
+		return UNIX_EPOCH.checked_add(Duration::from_secs(timestamp)).ok_or(format!("timestamp too large: {arg}"));
+	}
+
+	let (number, unit) = arg.split_at(arg.len() - arg.chars().last().map_or(0, |c| c.len_utf8()));
+	let number = number.parse::<u64>().map_err(|_| format!("invalid duration or timestamp: {arg}"))?;
+	let unit_seconds = match unit {
+		"s" => 1,
+		"m" => 60,
+		"h" => 60 * 60,
+		"d" => 60 * 60 * 24,
+		"w" => 60 * 60 * 24 * 7,
+		_ => return Err(format!("unknown duration unit in {arg}, expected one of s, m, h, d, w")),
 	};
+	number.checked_mul(unit_seconds)
+		.and_then(|seconds| SystemTime::now().checked_sub(Duration::from_secs(seconds)))
+		.ok_or(format!("duration too large: {arg}"))
+}
 
-	// convert to i32
-	let rx = caps.name("rx").unwrap().as_str().parse::<i32>().unwrap();
-	let ry = caps.name("ry").unwrap().as_str().parse::<i32>().unwrap();
-	// print chunk coordinates using std err to not mess up the output when piping to a file
-	eprintln!("---------- reading chunk: {}, {} ----------", rx, ry);
+// open a file for reading while letting other programs keep it open for writing
+// this allows reading worlds that are open in minecraft or a running server
+fn open_shared(path: &Path) -> std::io::Result<File> {
+	let mut options = std::fs::OpenOptions::new();
+	options.read(true);
+	#[cfg(windows)]
+	{
+		use std::os::windows::fs::OpenOptionsExt;
+		// FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE
+		options.share_mode(0x1 | 0x2 | 0x4);
+	}
+	options.open(path)
+}
 
-	// check if file is not empty/corrupted
-	let metadata = std::fs::metadata(file_path.clone()).expect("failed to get metadata");
-	if metadata.len() == 0 {
-		return (signs,books);
+// guess if minecraft or a server has the world open
+// the game writes session.lock when it opens a world and saves level.dat every few minutes while it runs,
+// a lock that isn't visible to other programs on every os can't be checked so a recent save is taken as a hint
+// servers save every 5 minutes by default
+const WORLD_IN_USE_WINDOW: Duration = Duration::from_secs(6 * 60);
+
+fn world_in_use(save_path: &Path) -> bool {
+	if !save_path.join("session.lock").exists() {
+		return false;
+	}
+	let saved = match std::fs::metadata(save_path.join("level.dat")).and_then(|metadata| metadata.modified()) {
+		Ok(saved) => saved,
+		Err(_e) => return false,
+	};
+	saved.elapsed().is_ok_and(|elapsed| elapsed < WORLD_IN_USE_WINDOW)
+}
+
+// explain sharing violations on windows which happen when minecraft holds the file open
+fn sharing_violation_hint(e: &std::io::Error) -> &'static str {
+	// ERROR_SHARING_VIOLATION
+	if cfg!(windows) && e.raw_os_error() == Some(32) {
+		" (the world is still open in minecraft, try closing it first)"
+	} else {
+		""
 	}
+}
 
+// read the version of a world and extract everything from its region files
+fn extract_world(save_path: &Path, opts: &Opts, pool: &threadpool::ThreadPool) -> Option<World> {
+	// check if save folder exists, a region piped to stdin has no save folder
+	if !opts.stdin && !save_path.exists() {
+		error!("save folder does not exist");
+		return None;
+	}
 
-	// open file
-	let mut region_file = File::open(file_path).expect("failed to open file");
+	// worlds can also be read straight from a .tar.gz backup
+	let archive_name = archive_name(save_path);
+	if archive_name.is_some() && opts.cubic_chunks {
+		error!("cubic chunks worlds can't be read from an archive");
+		return None;
+	}
 
-	// read headers
-	for x in 0..32 {
-		for z in 0..32 {
-			// seek to header
-			let offset = (x + z * 32) * 4;
-			region_file.seek(std::io::SeekFrom::Start(offset as u64)).expect("failed to seek");
+	// check if save folder is a directory
+	if archive_name.is_none() && !opts.stdin && !save_path.is_dir() {
+		error!("save folder is not a directory");
+		return None;
+	}
 
-			// read 4 bytes
-			let mut header = [0; 4];
-			region_file.read_exact(&mut header).expect("failed to read header");
+	// chunks that are saved while they are read can be cut off
+	if archive_name.is_none() && !opts.stdin && world_in_use(save_path) {
+		if opts.live {
+			info!("the world was saved a moment ago and may be in use, chunks that fail to read are read again");
+		} else {
+			warn!("the world was saved a moment ago and may be in use by minecraft or a server, chunks that are saved while they are read can fail, use --live to read them again");
+		}
+	}
 
-			// first 3 bytes are offset
-			// last byte is number of 4KiB sectors
-			let offset = (header[0] as u32) << 16 | (header[1] as u32) << 8 | (header[2] as u32);
-			let sectors = header[3] as u32;
+	// get save version
+	let mut archive_root = None;
+	let version_nbt: Option<LevelDat> = if archive_name.is_some() {
+		// the world root is the folder in the archive that holds level.dat
+		let (root, level_dats) = match find_level_dat_in_archive(save_path) {
+			Some(found) => found,
+			None => {
+				error!("save version does not exist");
+				return None;
+			}
+		};
+		archive_root = Some(root);
+		// a forced version is used as is so a broken level.dat can still be read around
+		if opts.force_version.is_none() {
+			Some(parse_level_dat(level_dats.into_iter().map(|(name, data)| (name, Ok(data))).collect())?)
+		} else {
+			None
+		}
+	} else if opts.force_version.is_some() {
+		// level.dat isn't needed when the version is forced
+		None
+	} else {
+		let level_dats = LEVEL_DAT_NAMES.iter()
+			.map(|name| (*name, save_path.join(name)))
+			.filter(|(_, path)| path.exists())
+			.map(|(name, path)| (name, open_shared(&path).and_then(|mut file| {
+				let mut data = Vec::new();
+				file.read_to_end(&mut data)?;
+				Ok(data)
+			})))
+			.collect();
+		Some(parse_level_dat(level_dats)?)
+	};
 
-			// check if chunk is present
-			if sectors == 0 {
-				continue;
+	// if Version is None then we are using an old version of minecraft
+	// fallback to old version
+	let seed = version_nbt.as_ref().and_then(|level_dat| level_dat.data.seed());
+	let spawn = version_nbt.as_ref().and_then(|level_dat| level_dat.data.spawn());
+	let version = match (opts.force_version, version_nbt) {
+		(Some(id), _) => forced_version(id),
+		(None, Some(version_nbt)) => match version_nbt.data.version {
+			Some(version) => version,
+			None => {
+				LevelDatDataVersion {
+					id: version_nbt.data.old_version,
+					name: "old".to_string(),
+					snapshot: false
+				}
 			}
+		},
+		(None, None) => unreachable!("level.dat is read unless the version is forced"),
+	};
 
-			// seek to chunk
-			let chunk_offset = offset as u64 * 4096;
-			region_file.seek(std::io::SeekFrom::Start(chunk_offset)).expect("failed to seek");
+	// print version
+	info!("world_version: {} id: {}", version.name, version.id);
+	if version.snapshot {
+		info!("this is a snapshot, every chunk format is tried for chunks that don't match its version");
+	}
 
-			// read chunk length of remaining chunk bytes
-			let mut length = [0; 4];
-			region_file.read_exact(&mut length).expect("failed to read length");
+	// spawn is in block coordinates and chunks are 16 blocks wide
+	let spawn_area = match (opts.spawn_only, spawn) {
+		(Some(radius), Some((spawn_x, _, spawn_z))) => Some((spawn_x.div_euclid(16), spawn_z.div_euclid(16), radius)),
+		(Some(_), None) => {
+			error!("--spawn-only needs the spawn point from level.dat");
+			return None;
+		}
+		(None, _) => None,
+	};
 
-			// convert from big endian
-			let length = u32::from_be_bytes(length);
 
-			// get compression type (5th byte)
-			// 1 = gzip
-			// 2 = zlib
-			// 3 = uncompressed
-			let mut compression_type = [0; 1];
-			region_file.read_exact(&mut compression_type).expect("failed to read compression type");
+	// get all files in region folder
+	// cubic chunks keeps the cubes that hold the tile entities in region3d
+	let region_folder = if opts.cubic_chunks {
+		"region3d"
+	} else {
+		"region"
+	};
+	// from 1.17 on entities like item frames are kept in region files of their own in the entities folder
+	let read_entities = !opts.cubic_chunks && chunk_format_of(&version) != ChunkFormat::Legacy
+		&& (opts.book_sources.contains(&BookSource::Frame) || opts.book_sources.contains(&BookSource::Entity));
+	let folders: Vec<(Dimension, &str, bool)> = opts.dimensions.iter()
+		.flat_map(|dimension| [(*dimension, region_folder, false), (*dimension, "entities", true)])
+		.filter(|(_, _, entities)| !entities || read_entities)
+		.collect();
+	let mut signs:Vec<ChunkLevelTileEntities> = Vec::new();
 
-			// if compression type is zlib read the chunk
-			if compression_type[0] != 2 {
-				println!("unsupported compression type: {}", compression_type[0]);
-				continue;
-			}
+	// create a channel to send the signs from the threads
+	let (tx, rx) = std::sync::mpsc::channel();
+	let panics_before = pool.panic_count();
 
-			let mut chunk = vec![0; (length-1) as usize];
-			region_file.read_exact(&mut chunk).expect("failed to read chunk");
-
-			let mut buf = vec![];
-			ZlibDecoder::new(&chunk[..]).read_to_end(&mut buf).unwrap();
-			
-			
-			/*
-			let val:Value = match fastnbt::from_bytes(buf.as_slice()) {
-				Ok(val) => val,
+	// the threads filter what they found before counting it for --limit
+	let shared_opts = std::sync::Arc::new(opts.clone());
+
+	let mut number_of_files = 0;
+	if opts.stdin {
+		// the region coordinates aren't known without a file name but block entities have their own coordinates
+		let mut data = Vec::new();
+		time_phase(Phase::Reading, || std::io::stdin().lock().read_to_end(&mut data)).expect("failed to read stdin");
+		let size = data.len() as u64;
+		let mut result = with_region_info(extract_signs_from_mca(std::io::Cursor::new(data), 0, 0, version.clone(), &opts.extract_options()), Path::new("stdin"), size, Dimension::Overworld, None);
+		keep_wanted(&mut result, opts, &version);
+		tx.send(result).unwrap();
+		number_of_files += 1;
+	} else if let Some(archive_root) = &archive_root {
+		// stream the region files of the world out of the archive to the threads
+		let archive_file = match open_shared(save_path) {
+			Ok(archive_file) => archive_file,
+			Err(e) => {
+				error!("failed to open archive {}: {}", save_path.display(), e);
+				return None;
+			}
+		};
+		let mut archive = tar::Archive::new(GzDecoder::new(archive_file));
+		let entries = match archive.entries() {
+			Ok(entries) => entries,
+			Err(e) => {
+				error!("failed to read archive {}: {}", save_path.display(), e);
+				return None;
+			}
+		};
+		// a truncated or corrupt archive ends the entries with an error, the regions before it are still written
+		let archive_error = |e: std::io::Error| {
+			warn!("failed to read an entry of {}: {}", save_path.display(), e);
+			let region = save_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+			RegionResult { errors: vec![ReadError { region, ..chunk_error(Dimension::Overworld, None, ReadErrorKind::File, e.to_string()) }], ..Default::default() }
+		};
+		for entry in entries {
+			// regions are read from the archive one after the other so stop reading it
+			if stop_scanning() {
+				break;
+			}
+			let mut entry = match entry {
+				Ok(entry) => entry,
 				Err(e) => {
-					// print error and chunk coordinates
-					eprintln!("failed to read nbt in chunk: {}, {} with error {}", rx, ry, e);
-					//println!("data: {:?}", nbt::Blob::from_reader(&mut ZlibDecoder::new(&chunk[..])));
+					tx.send(archive_error(e)).unwrap();
+					number_of_files += 1;
+					continue;
+				}
+			};
+			let entry_path = match entry.path() {
+				Ok(entry_path) => entry_path.into_owned(),
+				Err(e) => {
+					tx.send(archive_error(e)).unwrap();
+					number_of_files += 1;
 					continue;
 				}
 			};
-			println!("val: {:?}", val);
-			continue; */
-
-			// comparison to old is needed because the old version has a higher version id
-			// then the new version
-			if version.id > 2730 && version.name != "old".to_owned() { 
-				let nbt_data: Chunk1_18 = match fastnbt::from_bytes(buf.as_slice()) {
-					Ok(nbt_data) => nbt_data,
-					Err(_e) => {
-						// print error and chunk coordinates
-						//eprintln!("failed to read nbt in chunk: {}, {} with error {}", rx, ry, e);
-						continue;
-					}
-				};
 
-				//println!("nbt_data: {:?}", nbt_data);
-	
-				for block_entity in nbt_data.block_entities {
-					// if block entity is a sign
-					if block_entity.id.ends_with("sign") {
-						signs.push(block_entity);
-					}
+			// only read region files of the world, backups can contain more than one world
+			let (dimension, entities) = match folders.iter().find(|(dimension, folder, _)| entry_path.parent() == Some(dimension.folder(archive_root, folder).as_path())) {
+				Some((dimension, _, entities)) => (*dimension, *entities),
+				None => continue,
+			};
+			let (rx, ry) = match entry_path.file_name().and_then(|name| name.to_str()).and_then(region_coordinates) {
+				Some(coordinates) => coordinates,
+				None => continue,
+			};
+			let mut thread_options = opts.extract_options();
+			thread_options.dimension = dimension;
+			thread_options.spawn_area = spawn_area;
+			thread_options.entities = entities;
 
-					// check if items are present
-					else if block_entity.items.is_some() {
-						// iterate over items
-						for item in block_entity.items.unwrap() {
-							if item.id.to_lowercase().ends_with("book") && !item.id.to_lowercase().ends_with("enchanted_book") && !item.id.to_lowercase().ends_with(":book") {
-								// convert to BookWithPos and push to vector
-								books.push(BookWithPos {
-									book: item.tag.unwrap(),
-									x: block_entity.x,
-									y: block_entity.y,
-									z: block_entity.z,
-								});
-							}
-						}
-					}
+			// regions without any of the chunks from --chunks don't have to be read
+			if !thread_options.region_wanted(rx, ry) {
+				continue;
+			}
+
+			// the archive keeps the modification time of every file
+			if let Some(modified_since) = opts.modified_since {
+				let modified = UNIX_EPOCH + Duration::from_secs(entry.header().mtime().unwrap_or(0));
+				if modified < modified_since {
+					continue;
 				}
-			} else if version.id > 2681 && version.name != "old".to_owned() {
-				let nbt_data: Chunk1_17 = match fastnbt::from_bytes(buf.as_slice()) {
-					Ok(nbt_data) => nbt_data,
-					Err(_e) => {
-						// print error and chunk coordinates
-						//eprintln!("failed to read nbt in chunk: {}, {} with error {}", rx, ry, e);
-						continue;
+			}
+
+			// the archive is decompressed while it is read so this counts as reading
+			let mut data = Vec::new();
+			if let Err(e) = time_phase(Phase::Reading, || entry.read_to_end(&mut data)) {
+				warn!("failed to read {} from {}: {}", entry_path.display(), save_path.display(), e);
+				tx.send(with_region_info(RegionResult::default(), &entry_path, 0, dimension, Some(e.to_string()))).unwrap();
+				number_of_files += 1;
+				continue;
+			}
+
+			// clone the sender
+			let thread_tx = tx.clone();
+			let thread_version = version.clone();
+			let thread_opts = shared_opts.clone();
+			let limit = opts.limit;
+			pool.execute(move || {
+				let file_name = entry_path.file_name().unwrap().to_string_lossy();
+				let size = data.len() as u64;
+				let mut result = match decompress_region_file(&file_name, &data[..]) {
+					Some(Ok(decompressed)) => with_region_info(extract_signs_from_mca(std::io::Cursor::new(decompressed), rx, ry, thread_version.clone(), &thread_options), &entry_path, size, dimension, None),
+					Some(Err(e)) => {
+						warn!("failed to decompress {}: {}", entry_path.display(), e);
+						with_region_info(RegionResult::default(), &entry_path, size, dimension, Some(e.to_string()))
 					}
+					None => with_region_info(extract_signs_from_mca(std::io::Cursor::new(data), rx, ry, thread_version.clone(), &thread_options), &entry_path, size, dimension, None),
 				};
-
-				//println!("nbt_data: {:?}", nbt_data);
-	
-				for block_entity in nbt_data.level.block_entities {
-					// if block entity is a sign
-					if block_entity.id.ends_with("sign") {
-						signs.push(block_entity);
+				keep_wanted(&mut result, &thread_opts, &thread_version);
+				count_found(&result, limit);
+				thread_tx.send(result).unwrap();
+			});
+			number_of_files += 1;
+		}
+	} else {
+		// with --threads-io regions are read on their own threads and parsed on the pool
+		// a parse thread waiting for the disk leaves its cpu idle, and a hard drive reading many files at once spends its time seeking
+		let reader = opts.threads_io.map(|threads_io| {
+			// two regions per parse thread keep them busy without holding much of the world in memory
+			(threadpool::Builder::new().num_threads(threads_io.max(1)).thread_name("reader".to_string()).build(), ReadAhead::new(pool.max_count() * 2))
+		});
+		for &(dimension, folder, entities) in &folders {
+			let region_path = dimension.folder(save_path, folder);
+			let region_files = match region_path.read_dir() {
+				Ok(region_files) => region_files,
+				Err(_e) => {
+					// worlds without any entities saved yet have no entities folder
+					if !entities {
+						warn!("{} does not exist", region_path.display());
 					}
+					continue;
+				}
+			};
+			for file in region_files {
+				let file = file.unwrap();
+				let file_path = file.path();
+				// region files are always named in ascii so this is something else that ended up in the folder
+				if file.file_name().to_str().is_none() {
+					warn!("skipping {} since its name isn't valid utf-8", file_path.display());
+					continue;
+				}
+				if replaced_by_anvil(&file_path) {
+					continue;
+				}
 
-					// check if items are present
-					else if block_entity.items.is_some() {
-						// iterate over items
-						for item in block_entity.items.unwrap() {
-							if item.id.to_lowercase().ends_with("book") && !item.id.to_lowercase().ends_with("enchanted_book") && !item.id.to_lowercase().ends_with(":book") {
-								// convert to BookWithPos and push to vector
-								books.push(BookWithPos {
-									book: item.tag.unwrap(),
-									x: block_entity.x,
-									y: block_entity.y,
-									z: block_entity.z,
-								});
-							}
-						}
+				// skip region files that haven't been written to since the given time
+				// this only looks at the filesystem so it is much cheaper than parsing the chunks
+				if let Some(modified_since) = opts.modified_since {
+					// the region is read anyway when its time can't be found out so nothing is missed
+					match file.metadata().and_then(|metadata| metadata.modified()) {
+						Ok(modified) if modified < modified_since => continue,
+						Ok(_modified) => {}
+						Err(e) => warn!("failed to get the modification time of {}: {}", file_path.display(), e),
 					}
 				}
-			}
-			//todo support version upgraded from/on 1.7 and below
-			else {
-				let nbt_data: Chunk = match fastnbt::from_bytes(buf.as_slice()) {
-					Ok(nbt_data) => nbt_data,
-					Err(_e) => {
-						// print error and chunk coordinates
-						//eprintln!("failed to read nbt in chunk: {}, {} with error {}", rx, ry, e);
-						continue;
-					}
-				};
-				// iterate over tile entities
-				for tile_entity in nbt_data.level.tile_entities {
-					// if tile entity is a sign
-					// convert to lowercase because somewhere between 1.12.2 and 1.9.4 the id changed from "minecraft:sign" to "Sign"
-					if tile_entity.id.to_lowercase().ends_with("sign") {
-						signs.push(tile_entity);
-					} 
-					// check if items are present
-					else if tile_entity.items.is_some() {
-						// iterate over items
-						for item in tile_entity.items.unwrap() {
-							if item.id.to_lowercase().ends_with("book") && !item.id.to_lowercase().ends_with("enchanted_book") && !item.id.to_lowercase().ends_with(":book") {
-								// check if item has a tag and book has a page
-								if !item.tag.is_some() {
-									continue;
+
+				// clone the sender
+				let thread_tx = tx.clone();
+				let thread_version = version.clone();
+				let thread_opts = shared_opts.clone();
+				let limit = opts.limit;
+				let mut thread_options = opts.extract_options();
+				thread_options.dimension = dimension;
+				thread_options.spawn_area = spawn_area;
+				thread_options.entities = entities;
+				let cubic_chunks = opts.cubic_chunks;
+				if let Some((reader_pool, read_ahead)) = &reader {
+					let read_ahead = read_ahead.clone();
+					let pool = pool.clone();
+					reader_pool.execute(move || {
+						if stop_scanning() {
+							thread_tx.send(RegionResult::default()).unwrap();
+							return;
+						}
+						let permit = read_ahead.acquire();
+						let read = read_region_file(file_path, &thread_options);
+						pool.execute(move || {
+							let mut result = match read {
+								Some((file_path, rx, ry, Ok(data))) => {
+									let size = data.len() as u64;
+									extract_signs_from_region_data(std::io::Cursor::new(data), &file_path, size, rx, ry, thread_version.clone(), &thread_options)
 								}
-								let book = item.tag.unwrap();
-								if !book.pages.is_some() {
-									continue;
+								Some((file_path, _, _, Err(e))) => {
+									warn!("failed to read {}: {}{}", file_path.display(), e, sharing_violation_hint(&e));
+									with_region_info(RegionResult::default(), &file_path, 0, thread_options.dimension, Some(e.to_string()))
 								}
-								// convert to BookWithPos and push to vector
-								books.push(BookWithPos {
-									book: book,
-									x: tile_entity.x,
-									y: tile_entity.y,
-									z: tile_entity.z,
-								});
-							}
-						}
-					}
+								None => RegionResult::default(),
+							};
+							drop(permit);
+							keep_wanted(&mut result, &thread_opts, &thread_version);
+							count_found(&result, limit);
+							thread_tx.send(result).unwrap();
+						});
+					});
+					number_of_files += 1;
+					continue;
 				}
-				// iterate over entities
-				for entity in nbt_data.level.entities {
-					// check if item is present
-					if entity.item.is_some() {
-						let item = entity.item.unwrap();
-						// check if item is a written book
-						if item.id.to_lowercase().ends_with("book") && !item.id.to_lowercase().ends_with("enchanted_book") {
-							// check if item has a tag and book has a title
-							if !item.tag.is_some() {
-								continue;
-							}
-							let book = item.tag.unwrap();
-							if !book.pages.is_some() {
-								continue;
-							}
-							// convert to BookWithPos and push to vector
-							books.push(BookWithPos {
-								book: book,
-								x: entity.pos[0] as i32,
-								y: entity.pos[1] as i32,
-								z: entity.pos[2] as i32,
-							});
-						}
+				pool.execute(move || {
+					// all regions are queued right away so the ones that weren't started are skipped here
+					if stop_scanning() {
+						thread_tx.send(RegionResult::default()).unwrap();
+						return;
 					}
-				}
+					// extract signs from mca file
+					let mut result = if cubic_chunks {
+						extract_signs_from_3dr_file(file_path, &thread_options)
+					} else {
+						extract_signs_from_mca_file(file_path, thread_version.clone(), &thread_options)
+					};
+					keep_wanted(&mut result, &thread_opts, &thread_version);
+					count_found(&result, limit);
+					thread_tx.send(result).unwrap();
+				});
+				number_of_files += 1;
 			}
 		}
+		// the readers queue the parsing so they have to be done before the pool can be waited for
+		if let Some((reader_pool, _)) = &reader {
+			reader_pool.join();
+		}
+	}
+	pool.join();
+	// a region that panicked never sends its result, once every sender is gone the results below stop at the last one that was sent
+	drop(tx);
+	let panics = pool.panic_count() - panics_before;
+	if panics > 0 {
+		error!("{} regions couldn't be read because of a panic, they are missing from the output", panics);
+	}
+
+	// collect all the results from the threads
+	let mut books:Vec<BookWithPos> = Vec::new();
+	let mut commands:Vec<CommandWithPos> = Vec::new();
+	let mut chunks:Vec<ChunkCount> = Vec::new();
+	let mut raw_signs:Vec<RawSign> = Vec::new();
+	let mut regions:Vec<RegionInfo> = Vec::new();
+	let mut errors:Vec<ReadError> = Vec::new();
+	let mut structures:Vec<StructureWithPos> = Vec::new();
+	let mut containers:Vec<ContainerWithPos> = Vec::new();
+	let mut block_entity_ids: HashMap<String, usize> = HashMap::new();
+	rx.iter().take(number_of_files).for_each(|result_from_thread| {
+		// the threads already left out what --min-y, --max-y, --include-text and --exclude-text don't want
+		signs.extend(result_from_thread.signs);
+		books.extend(result_from_thread.books);
+		commands.extend(result_from_thread.commands);
+		chunks.extend(result_from_thread.chunks);
+		raw_signs.extend(result_from_thread.raw_signs);
+		regions.extend(result_from_thread.regions);
+		errors.extend(result_from_thread.errors);
+		structures.extend(result_from_thread.structures);
+		containers.extend(result_from_thread.containers);
+		for (id, count) in result_from_thread.block_entity_ids {
+			*block_entity_ids.entry(id).or_default() += count;
+		}
+	});
+
+	// sort signs and books by dimension and then by --sort-by
+	// with --group-by region the signs and books of a region have to be next to each other so they are sorted by region first
+	let region = |x: i32, z: i32| match opts.group_by {
+		Some(GroupBy::Region) => (x.div_euclid(512), z.div_euclid(512)),
+		_ => (0, 0),
+	};
+	signs.sort_by(|a, b| {
+		a.dimension.cmp(&b.dimension).then(region(a.x, a.z).cmp(&region(b.x, b.z))).then(opts.sort_by.compare((a.x, a.y, a.z), (b.x, b.y, b.z)))
+	});
+	books.sort_by(|a, b| {
+		a.dimension.cmp(&b.dimension).then(region(a.x, a.z).cmp(&region(b.x, b.z))).then(opts.sort_by.compare((a.x, a.y, a.z), (b.x, b.y, b.z)))
+	});
+
+	// regions that were already being read when the limit was reached can add more than asked for
+	if let Some(limit) = opts.limit {
+		if LIMIT_REACHED.load(Ordering::Relaxed) {
+			info!("stopped after finding {} signs or books", limit);
+		}
+		signs.truncate(limit);
+		books.truncate(limit);
+	}
+
+	Some(World {
+		version,
+		seed,
+		spawn,
+		result: RegionResult { signs, books, commands, chunks, raw_signs, regions, structures, containers, block_entity_ids, errors, ..Default::default() },
+	})
+}
+
+// write how the sign lines of a world can be read and whether that fits the version
+fn write_validation(signs: &[ChunkLevelTileEntities], version: &LevelDatDataVersion, save_name: &str, opts: &Opts) {
+	let validations = validate_signs(signs);
+	let count = |format| validations.iter().filter(|validation| validation.format == format).count();
+	let (json, raw, broken) = (count(LineFormat::Json), count(LineFormat::Raw), count(LineFormat::Broken));
+	info!("{} json lines, {} raw lines, {} broken lines", json, raw, broken);
+
+	// lines are read as raw text for worlds from before data versions and as json for all others
+	let reads_raw = version.name == "old";
+	if !reads_raw && raw > json {
+		warn!("most lines are raw text but they are read as json, try --force-version 0");
+	} else if reads_raw && json > raw {
+		warn!("most lines are json but they are read as raw text, try --force-version with the data version of the world");
 	}
-	return (signs,books);
-}
\ No newline at end of file
+	if broken > 0 {
+		warn!("{} lines are broken json that can't be read either way", broken);
+	}
+
+	match opts.format {
+		Format::Text | Format::Markdown | Format::Mcfunction => {
+			let mut file = EncodedFile::create(format!("validate-{save_name}.txt"), opts.output_encoding).unwrap();
+			writeln!(file, "version: {} id: {}", version.name, version.id).unwrap();
+			writeln!(file, "lines are read as: {}", if reads_raw { "raw" } else { "json" }).unwrap();
+			writeln!(file, "json lines: {}", json).unwrap();
+			writeln!(file, "raw lines: {}", raw).unwrap();
+			writeln!(file, "broken lines: {}", broken).unwrap();
+			writeln!(file).unwrap();
+			for validation in validations {
+				writeln!(file, "{} line {}: {}: {}", location(validation.dimension, validation.x, validation.y, validation.z), validation.line, validation.format.name(), validation.text).unwrap();
+			}
+		}
+		Format::Json | Format::Geojson => write_json(format!("validate-{save_name}.json"), &validations, opts.pretty, opts.output_encoding),
+	}
+}
+
+// the version given with --force-version
+// 0 is for worlds from before data versions that store raw sign text
+fn forced_version(id: i32) -> LevelDatDataVersion {
+	LevelDatDataVersion {
+		id,
+		name: if id == 0 { "old" } else { "forced" }.to_string(),
+		snapshot: false,
+	}
+}
+
+// the name of a world read from a .tar.gz backup without the extension
+fn archive_name(save_path: &Path) -> Option<&str> {
+	let file_name = save_path.file_name()?.to_str()?;
+	file_name.strip_suffix(".tar.gz").or(file_name.strip_suffix(".tgz"))
+}
+
+fn main() {
+	let opts: Opts = Opts::parse_from(args_with_config(std::env::args_os().collect(), &Opts::command()));
+
+	// logs go to stderr, use --verbose or RUST_LOG=debug to see every region file or RUST_LOG=warn to only see problems
+	// stdout is only written to by --list-versions so it can't corrupt output that is piped somewhere
+	let default_filter = if opts.verbose { "debug" } else { "info" };
+	env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter))
+		.target(env_logger::Target::Stderr)
+		.init();
+
+	// the first ctrl-c stops reading and writes what was found, a second one exits right away
+	ctrlc::set_handler(|| {
+		if INTERRUPTED.swap(true, Ordering::Relaxed) {
+			std::process::exit(130);
+		}
+		warn!("interrupted, finishing the regions that are being read, press ctrl-c again to exit right away");
+	}).expect("failed to set ctrl-c handler");
+
+	// this is for checking if a world can be read before starting a long scan so nothing is extracted
+	if opts.list_versions {
+		for line in supported_versions() {
+			println!("{}", line);
+		}
+		return;
+	}
+
+	let profile = opts.profile;
+	if profile {
+		enable_profiling();
+	}
+	let started = Instant::now();
+	run(opts);
+	if profile {
+		report_profile(started.elapsed());
+	}
+}
+
+fn run(opts: Opts) {
+	// get number of threads
+	let num_threads = opts.threads();
+
+	// create thread pool, it is shared by all saves
+	let pool = threadpool::Builder::new().num_threads(num_threads).build();
+
+	if opts.watch && opts.save.len() > 1 {
+		error!("--watch can only follow one save");
+		return;
+	}
+
+	// the output files of a region read from stdin are named like a world called stdin
+	let saves = if opts.stdin { vec!["stdin".to_string()] } else { opts.save.clone() };
+	let mut summaries = Vec::new();
+	for save in &saves {
+		// ctrl-c also skips the saves that weren't read yet
+		if interrupted() {
+			break;
+		}
+		reset_limit();
+		// a save that panics was already reported by the panic message so the next one is read
+		let found = std::panic::catch_unwind(AssertUnwindSafe(|| run_save(Path::new(save), &opts, &pool))).unwrap_or(None);
+		summaries.push((save, found));
+	}
+
+	if saves.len() > 1 {
+		info!("summary:");
+		for (save, found) in summaries {
+			match found {
+				Some((signs, books)) => info!("{}: {} signs and {} books", save, signs, books),
+				None => info!("{}: failed", save),
+			}
+		}
+	}
+}
+
+// extract a single save and write its output, returns the number of signs and books or none if it couldn't be read
+fn run_save(save_path: &Path, opts: &Opts, pool: &threadpool::ThreadPool) -> Option<(usize, usize)> {
+	let world = extract_world(save_path, opts, pool)?;
+	if interrupted() {
+		warn!("writing the signs and books that were found before the scan was interrupted");
+	}
+	// a folder name that isn't valid utf-8 still names the output files with its invalid parts replaced
+	let save_name = match archive_name(save_path) {
+		Some(name) => name.to_string(),
+		None => save_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_else(|| "world".to_string()),
+	};
+	let save_name = save_name.as_str();
+	let version = world.version;
+	let RegionResult { signs, books, mut commands, mut chunks, mut raw_signs, mut regions, mut structures, mut containers, block_entity_ids, mut errors, .. } = world.result;
+	let found = (signs.len(), books.len());
+
+	// the ids are printed as they are stored so differences in casing between versions and mods show up
+	if opts.list_block_entities {
+		let mut block_entity_ids: Vec<(String, usize)> = block_entity_ids.into_iter().collect();
+		block_entity_ids.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+		info!("block entities:");
+		for (id, count) in block_entity_ids {
+			info!("{:>9} {}", count, id);
+		}
+	}
+
+	// the report is written even without errors so a script can always read it
+	if let Some(report_path) = &opts.error_report {
+		errors.sort_by(|a, b| {
+			a.dimension.cmp(&b.dimension).then(a.region.cmp(&b.region)).then(a.chunk_x.cmp(&b.chunk_x)).then(a.chunk_z.cmp(&b.chunk_z))
+		});
+		info!("{} errors while reading the world", errors.len());
+		write_json(opts.output_path(report_path, save_name), &errors, opts.pretty, opts.output_encoding);
+	}
+
+	// the report replaces the normal output since it is only for finding out how the world should be read
+	if opts.validate {
+		write_validation(&signs, &version, save_name, opts);
+		info!("done!");
+		return Some(found);
+	}
+
+	// the seed and spawn point help others find the signs when the extracted text is shared
+	if opts.world_info {
+		let mut file = EncodedFile::create(format!("world-info-{save_name}.txt"), opts.output_encoding).unwrap();
+		writeln!(file, "version: {} id: {}", version.name, version.id).unwrap();
+		match world.seed {
+			Some(seed) => writeln!(file, "seed: {}", seed).unwrap(),
+			None => writeln!(file, "seed: unknown").unwrap(),
+		}
+		match world.spawn {
+			Some((x, y, z)) => writeln!(file, "spawn: x: {} y: {} z: {}", x, y, z).unwrap(),
+			None => writeln!(file, "spawn: unknown").unwrap(),
+		}
+	}
+
+	// villager workstations are stored in the poi folder which uses the same region format
+	if opts.follow_poi {
+		let (tx_poi, rx_poi) = std::sync::mpsc::channel();
+		let mut number_of_poi_files = 0;
+		for dimension in &opts.dimensions {
+			let poi_path = dimension.folder(save_path, "poi");
+			let poi_files = match poi_path.read_dir() {
+				Ok(poi_files) => poi_files,
+				Err(_e) => {
+					warn!("{} does not exist", poi_path.display());
+					continue;
+				}
+			};
+			for file in poi_files {
+				let file_path = file.unwrap().path();
+				let thread_tx_poi = tx_poi.clone();
+				let dimension = *dimension;
+				pool.execute(move || {
+					if interrupted() {
+						thread_tx_poi.send(Vec::new()).unwrap();
+						return;
+					}
+					thread_tx_poi.send(extract_workstations_from_mca(file_path, dimension)).unwrap();
+				});
+				number_of_poi_files += 1;
+			}
+		}
+		pool.join();
+		drop(tx_poi);
+		let _writing = PhaseTimer::start(Phase::Writing);
+
+		let mut workstations:Vec<PoiWithPos> = Vec::new();
+		rx_poi.iter().take(number_of_poi_files).for_each(|workstations_from_thread| {
+			workstations.extend(workstations_from_thread);
+		});
+
+		// sort workstations by x then z
+		workstations.sort_by(|a, b| {
+			a.dimension.cmp(&b.dimension).then(a.x.cmp(&b.x)).then(a.z.cmp(&b.z)).then(a.y.cmp(&b.y))
+		});
+
+		// write workstations to file
+		let mut file = EncodedFile::create(format!("poi-{save_name}.txt"), opts.output_encoding).unwrap();
+		for workstation in workstations {
+			writeln!(file, "========== workstation location: {} ==========", location(workstation.dimension, workstation.x, workstation.y, workstation.z)).unwrap();
+			writeln!(file, "type: {}", workstation.poi_type).unwrap();
+			writeln!(file).unwrap();
+		}
+	}
+
+	// everything from here on only writes the output, except for reading the old world of --diff
+	let mut writing = PhaseTimer::start(Phase::Writing);
+
+	// write the per chunk sign and book counts
+	if let Some(density_path) = &opts.density {
+		chunks.sort_by(|a, b| {
+			a.dimension.cmp(&b.dimension).then(a.chunk_x.cmp(&b.chunk_x)).then(a.chunk_z.cmp(&b.chunk_z))
+		});
+		// the entities of a chunk are counted from their own file since 1.17 so a chunk can be there twice
+		chunks.dedup_by(|duplicate, chunk| {
+			let same = (duplicate.dimension, duplicate.chunk_x, duplicate.chunk_z) == (chunk.dimension, chunk.chunk_x, chunk.chunk_z);
+			if same {
+				chunk.signs += duplicate.signs;
+				chunk.books += duplicate.books;
+			}
+			same
+		});
+		let mut file = EncodedFile::create(opts.output_path(density_path, save_name), opts.output_encoding).unwrap();
+		writeln!(file, "dimension,chunk_x,chunk_z,sign_count,book_count").unwrap();
+		for chunk in chunks {
+			writeln!(file, "{},{},{},{},{}", chunk.dimension.name(), chunk.chunk_x, chunk.chunk_z, chunk.signs, chunk.books).unwrap();
+		}
+	}
+
+	// write every region file that was read and whether it could be read
+	if let Some(manifest_path) = &opts.manifest {
+		regions.sort_by(|a, b| a.dimension.cmp(&b.dimension).then(a.file.cmp(&b.file)));
+		let mut file = EncodedFile::create(opts.output_path(manifest_path, save_name), opts.output_encoding).unwrap();
+		writeln!(file, "dimension,file,size,chunks,failed_chunks,error").unwrap();
+		for region in regions {
+			let error = region.error.map(|error| format!("\"{}\"", error.replace('"', "\"\""))).unwrap_or_default();
+			writeln!(file, "{},{},{},{},{},{}", region.dimension.name(), region.file, region.size, region.chunks, region.failed_chunks, error).unwrap();
+		}
+	}
+
+	// write the raw nbt of the signs
+	if let Some(dump_path) = &opts.dump_raw_signs {
+		raw_signs.sort_by(|a, b| {
+			a.dimension.cmp(&b.dimension).then(a.x.cmp(&b.x)).then(a.z.cmp(&b.z)).then(a.y.cmp(&b.y))
+		});
+		let mut file = EncodedFile::create(opts.output_path(dump_path, save_name), opts.output_encoding).unwrap();
+		for sign in raw_signs {
+			writeln!(file, "========== sign location: {} ==========", location(sign.dimension, sign.x, sign.y, sign.z)).unwrap();
+			writeln!(file, "{:#?}\n", sign.nbt).unwrap();
+		}
+	}
+
+	// signs with coordinates written on them
+	if opts.waypoints {
+		let finder = WaypointFinder::new();
+		let waypoints: Vec<Waypoint> = signs.iter()
+			.flat_map(|sign| finder.find(&sign_record(sign, &version, opts.line_options())))
+			.collect();
+		match opts.format {
+			Format::Text | Format::Markdown | Format::Mcfunction => {
+				let mut file = EncodedFile::create(format!("waypoints-{save_name}.txt"), opts.output_encoding).unwrap();
+				for waypoint in waypoints {
+					writeln!(file, "========== waypoint sign location: {} ==========", location(waypoint.dimension, waypoint.x, waypoint.y, waypoint.z)).unwrap();
+					match waypoint.target_y {
+						Some(target_y) => writeln!(file, "points to: {},{},{}", waypoint.target_x, target_y, waypoint.target_z).unwrap(),
+						None => writeln!(file, "points to: {},~,{}", waypoint.target_x, waypoint.target_z).unwrap(),
+					}
+					writeln!(file).unwrap();
+				}
+			}
+			Format::Json | Format::Geojson => write_json(format!("waypoints-{save_name}.json"), &waypoints, opts.pretty, opts.output_encoding),
+		}
+	}
+
+	// a searchable database of everything that was found
+	if let Some(sqlite_path) = &opts.sqlite {
+		let sqlite_path = opts.output_path(sqlite_path, save_name);
+		let sign_records: Vec<SignRecord> = signs.iter().map(|sign| sign_record(sign, &version, opts.line_options())).collect();
+		let book_records: Vec<BookRecord> = books.iter().map(|book| book_record(book, opts.keep_formatting)).collect();
+		if let Err(e) = write_sqlite(&sqlite_path, &sign_records, &book_records) {
+			error!("failed to write {}: {}", sqlite_path.display(), e);
+		}
+	}
+
+	// signs crafted to crash clients that look at them
+	// old versions store raw text which can't be used for this
+	if opts.detect_exploits && version.name != "old" {
+		let suspicious: Vec<SuspiciousLine> = signs.iter().flat_map(suspicious_lines).collect();
+		if !suspicious.is_empty() {
+			warn!("found {} suspicious sign lines", suspicious.len());
+		}
+		match opts.format {
+			Format::Text | Format::Markdown | Format::Mcfunction => {
+				let mut file = EncodedFile::create(format!("exploits-{save_name}.txt"), opts.output_encoding).unwrap();
+				for line in suspicious {
+					writeln!(file, "========== suspicious sign location: {} ==========", location(line.dimension, line.x, line.y, line.z)).unwrap();
+					writeln!(file, "{}: {}", if line.back { "back line" } else { "line" }, line.line).unwrap();
+					writeln!(file, "length: {}", line.length).unwrap();
+					writeln!(file, "reason: {}", line.reason).unwrap();
+					writeln!(file).unwrap();
+				}
+			}
+			Format::Json | Format::Geojson => write_json(format!("exploits-{save_name}.json"), &suspicious, opts.pretty, opts.output_encoding),
+		}
+	}
+
+	// jigsaw and structure blocks
+	if opts.structures {
+		structures.sort_by(|a, b| {
+			a.dimension.cmp(&b.dimension).then(a.x.cmp(&b.x)).then(a.z.cmp(&b.z)).then(a.y.cmp(&b.y))
+		});
+		match opts.format {
+			Format::Text | Format::Markdown | Format::Mcfunction => {
+				let mut file = EncodedFile::create(format!("structures-{save_name}.txt"), opts.output_encoding).unwrap();
+				for structure in structures {
+					writeln!(file, "========== {} location: {} ==========", structure.block, location(structure.dimension, structure.x, structure.y, structure.z)).unwrap();
+					for (name, value) in structure.fields {
+						writeln!(file, "{}: {}", name, value).unwrap();
+					}
+					writeln!(file).unwrap();
+				}
+			}
+			Format::Json | Format::Geojson => write_json(format!("structures-{save_name}.json"), &structures, opts.pretty, opts.output_encoding),
+		}
+	}
+
+	if opts.player_books {
+		if archive_name(save_path).is_some() {
+			warn!("--player-books can't read the playerdata folder of an archive");
+		} else {
+			let books = read_player_books(save_path, opts.book_tag_path.as_deref());
+			info!("found {} books in player inventories", books.len());
+			match opts.format {
+				Format::Text | Format::Markdown | Format::Mcfunction => {
+					let mut file = EncodedFile::create(format!("player-books-{save_name}.txt"), opts.output_encoding).unwrap();
+					let mut player: Option<String> = None;
+					for book in books {
+						// books are read one player file at a time so every player gets one header
+						if player.as_deref() != Some(book.uuid.as_str()) {
+							match &book.name {
+								Some(name) => writeln!(file, "########## player: {} ({}) ##########\n", name, book.uuid).unwrap(),
+								None => writeln!(file, "########## player: {} ##########\n", book.uuid).unwrap(),
+							}
+							player = Some(book.uuid.clone());
+						}
+						let slot = book.slot.map(|slot| format!(" slot {}", slot)).unwrap_or_default();
+						writeln!(file, "=========== book in {}{} ==========", book.inventory, slot).unwrap();
+						write_book_text(&mut file, book.book, opts.keep_formatting, opts.flatten_books);
+					}
+				}
+				Format::Json | Format::Geojson => {
+					let books: Vec<PlayerBookRecord> = books.into_iter().map(|book| PlayerBookRecord {
+						pages: book_pages_text(&book.book, opts.keep_formatting),
+						uuid: book.uuid,
+						name: book.name,
+						inventory: book.inventory,
+						slot: book.slot,
+						title: book.book.title,
+						author: book.book.author,
+						custom_name: book.book.custom_name,
+					}).collect();
+					write_json(format!("player-books-{save_name}.json"), &books, opts.pretty, opts.output_encoding);
+				}
+			}
+		}
+	}
+
+	if opts.data_text {
+		if archive_name(save_path).is_some() {
+			warn!("--data-text can't read the data folder of an archive");
+		} else {
+			let texts = read_data_text(save_path);
+			match opts.format {
+				Format::Text | Format::Markdown | Format::Mcfunction => {
+					let mut file = EncodedFile::create(format!("data-text-{save_name}.txt"), opts.output_encoding).unwrap();
+					for text in texts {
+						writeln!(file, "{} {}: {}", text.file, text.path, text.text).unwrap();
+					}
+				}
+				Format::Json | Format::Geojson => write_json(format!("data-text-{save_name}.json"), &texts, opts.pretty, opts.output_encoding),
+			}
+		}
+	}
+
+	if opts.containers {
+		containers.sort_by(|a, b| {
+			a.dimension.cmp(&b.dimension).then(a.x.cmp(&b.x)).then(a.z.cmp(&b.z)).then(a.y.cmp(&b.y))
+		});
+		match opts.format {
+			Format::Text | Format::Markdown | Format::Mcfunction => {
+				let mut file = EncodedFile::create(format!("containers-{save_name}.txt"), opts.output_encoding).unwrap();
+				write_containers_text(&mut file, &containers);
+			}
+			Format::Json | Format::Geojson => write_json(format!("containers-{save_name}.json"), &containers, opts.pretty, opts.output_encoding),
+		}
+	}
+
+	if opts.commands {
+		// signs can run commands when clicked, these are stored as a clickEvent in the json
+		// old versions store raw text so they can't have click events
+		if version.name != "old" {
+			for sign in &signs {
+				for command in sign_commands(sign) {
+					commands.push(CommandWithPos {
+						command,
+						source: "sign".to_string(),
+						dimension: sign.dimension,
+						x: sign.x,
+						y: sign.y,
+						z: sign.z,
+					});
+				}
+			}
+		}
+
+		// sort commands by x then z
+		commands.sort_by(|a, b| {
+			a.dimension.cmp(&b.dimension).then(a.x.cmp(&b.x)).then(a.z.cmp(&b.z)).then(a.y.cmp(&b.y))
+		});
+
+		// write commands to file
+		let mut file = EncodedFile::create(format!("commands-{save_name}.txt"), opts.output_encoding).unwrap();
+		for command in commands {
+			writeln!(file, "========== command location: {} ==========", location(command.dimension, command.x, command.y, command.z)).unwrap();
+			writeln!(file, "source: {}", command.source).unwrap();
+			writeln!(file, "command: {}", command.command).unwrap();
+			writeln!(file).unwrap();
+		}
+	}
+
+	// compare against the old world instead of writing everything
+	if let Some(old_save) = &opts.diff {
+		drop(writing);
+		let old_world = extract_world(Path::new(old_save), opts, pool)?;
+		// a diff against a partly read world would list everything that wasn't read as changed
+		if interrupted() {
+			warn!("the diff was not written since the scan was interrupted");
+			return None;
+		}
+		writing = PhaseTimer::start(Phase::Writing);
+		let old_signs = old_world.result.signs.iter().map(|sign| sign_record(sign, &old_world.version, opts.line_options())).collect();
+		let new_signs = signs.iter().map(|sign| sign_record(sign, &version, opts.line_options())).collect();
+		let diff = DiffReport {
+			signs: diff_signs(old_signs, new_signs),
+			books: diff_books(old_world.result.books.iter().map(|book| book_record(book, opts.keep_formatting)).collect(), books.iter().map(|book| book_record(book, opts.keep_formatting)).collect()),
+		};
+		match opts.format {
+			Format::Text | Format::Markdown | Format::Mcfunction => {
+				let mut file = EncodedFile::create(format!("diff-{save_name}.txt"), opts.output_encoding).unwrap();
+				write_diff_text(&mut file, &diff);
+			}
+			Format::Json | Format::Geojson => write_json(format!("diff-{save_name}.json"), &diff, opts.pretty, opts.output_encoding),
+		}
+		drop(writing);
+		info!("done!");
+		return Some(found);
+	}
+
+	// what every region holds before the changes after each save are compared with it
+	let watched = (opts.watch && !interrupted()).then(|| region_records(&signs, &books, &version, opts));
+
+	// counted before --dedup-books so the pages of every copy are counted
+	if opts.book_stats {
+		let stats = book_stats(&books);
+		info!("book stats:");
+		info!("{:>9} books", stats.books);
+		info!("{:>9} authors", stats.authors);
+		info!("{:>9} titles", stats.titles);
+		info!("{:>9} pages", stats.pages);
+	}
+
+	let books = if opts.dedup_books {
+		dedup_books(books)
+	} else {
+		books
+	};
+
+	// write signs and books in the chosen format
+	match &opts.shards {
+		// every save gets its own directory in the shards directory when there are more
+		Some(directory) if opts.save.len() > 1 => write_shards(&directory.join(save_name), signs, books, &version, opts),
+		Some(directory) => write_shards(directory, signs, books, &version, opts),
+		None => write_signs_and_books(Path::new(""), save_name, signs, books, &version, opts),
+	}
+	drop(writing);
+	info!("done!");
+
+	if let Some(records) = watched {
+		if archive_name(save_path).is_some() {
+			warn!("--watch can't follow an archive");
+			return Some(found);
+		}
+		watch_world(save_path, save_name, &version, records, opts);
+	}
+	Some(found)
+}
+
+// write signs and books in the chosen format to files named after the save in a directory
+fn write_signs_and_books(directory: &Path, name: &str, signs: Vec<ChunkLevelTileEntities>, books: Vec<BookWithPos>, version: &LevelDatDataVersion, opts: &Opts) {
+	let path = |kind: &str, extension: &str| directory.join(format!("{kind}-{name}.{extension}"));
+	match opts.format {
+		Format::Text if opts.combined => {
+			// both sections go to the same file one after the other
+			let mut file = EncodedFile::create(path("report", "txt"), opts.output_encoding).unwrap();
+			writeln!(file, "========== signs ==========\n").unwrap();
+			write_signs_text(&mut file, signs, version, opts);
+			writeln!(file, "========== books ==========\n").unwrap();
+			write_books_text(&mut file, books, opts);
+		}
+		Format::Text => {
+			let mut file = EncodedFile::create(path("signs", "txt"), opts.output_encoding).unwrap();
+			write_signs_text(&mut file, signs, version, opts);
+
+			let mut file = EncodedFile::create(path("books", "txt"), opts.output_encoding).unwrap();
+			write_books_text(&mut file, books, opts);
+		}
+		Format::Markdown if opts.combined => {
+			let mut file = EncodedFile::create(path("report", "md"), opts.output_encoding).unwrap();
+			writeln!(file, "# Signs\n").unwrap();
+			write_signs_markdown(&mut file, &signs, version);
+			writeln!(file, "# Books\n").unwrap();
+			write_books_markdown(&mut file, &books);
+		}
+		Format::Markdown => {
+			let mut file = EncodedFile::create(path("signs", "md"), opts.output_encoding).unwrap();
+			write_signs_markdown(&mut file, &signs, version);
+
+			let mut file = EncodedFile::create(path("books", "md"), opts.output_encoding).unwrap();
+			write_books_markdown(&mut file, &books);
+		}
+		// signs and books are in different files even with --combined since books can't be placed with a command
+		Format::Mcfunction => {
+			let mut file = EncodedFile::create(path("signs", "mcfunction"), opts.output_encoding).unwrap();
+			write_signs_mcfunction(&mut file, &signs, version);
+
+			let mut file = EncodedFile::create(path("books", "txt"), opts.output_encoding).unwrap();
+			write_books_text(&mut file, books, opts);
+		}
+		Format::Json if opts.combined => {
+			let report = Report {
+				signs: signs.iter().map(|sign| sign_record(sign, version, opts.line_options())).collect(),
+				books: books.iter().map(|book| book_record(book, opts.keep_formatting)).collect(),
+			};
+			write_json(path("report", "json"), &report, opts.pretty, opts.output_encoding);
+		}
+		Format::Json if opts.json_per_line => {
+			let signs = signs.iter().map(|sign| sign_record(sign, version, opts.line_options()));
+			write_json_lines(path("signs", "ndjson"), signs, opts.output_encoding);
+
+			let books = books.iter().map(|book| book_record(book, opts.keep_formatting));
+			write_json_lines(path("books", "ndjson"), books, opts.output_encoding);
+		}
+		Format::Json => {
+			let signs: Vec<SignRecord> = signs.iter().map(|sign| sign_record(sign, version, opts.line_options())).collect();
+			write_json(path("signs", "json"), &signs, opts.pretty, opts.output_encoding);
+
+			let books: Vec<BookRecord> = books.iter().map(|book| book_record(book, opts.keep_formatting)).collect();
+			write_json(path("books", "json"), &books, opts.pretty, opts.output_encoding);
+		}
+		Format::Geojson => {
+			let signs = signs.iter().map(|sign| {
+				let record = sign_record(sign, version, opts.line_options());
+				point_feature(record.x, record.z, &record)
+			});
+			let books = books.iter().map(|book| {
+				let record = book_record(book, opts.keep_formatting);
+				point_feature(record.x, record.z, &record)
+			});
+			if opts.combined {
+				write_json(path("report", "geojson"), &feature_collection(signs.chain(books).collect()), opts.pretty, opts.output_encoding);
+			} else {
+				write_json(path("signs", "geojson"), &feature_collection(signs.collect()), opts.pretty, opts.output_encoding);
+				write_json(path("books", "geojson"), &feature_collection(books.collect()), opts.pretty, opts.output_encoding);
+			}
+		}
+	}
+}
+
+// the signs and books of a single region
+type Shard = (Vec<ChunkLevelTileEntities>, Vec<BookWithPos>);
+
+// write the signs and books of every region to their own files in a directory, using all threads
+// signs and books are already sorted so every file is sorted too
+fn write_shards(directory: &Path, signs: Vec<ChunkLevelTileEntities>, books: Vec<BookWithPos>, version: &LevelDatDataVersion, opts: &Opts) {
+	std::fs::create_dir_all(directory).expect("failed to create shard directory");
+
+	// regions are 512 blocks wide
+	let mut shards: BTreeMap<(Dimension, i32, i32), Shard> = BTreeMap::new();
+	for sign in signs {
+		shards.entry((sign.dimension, sign.x.div_euclid(512), sign.z.div_euclid(512))).or_default().0.push(sign);
+	}
+	for book in books {
+		shards.entry((book.dimension, book.x.div_euclid(512), book.z.div_euclid(512))).or_default().1.push(book);
+	}
+
+	// split the regions evenly between the threads
+	let mut shards: Vec<_> = shards.into_iter().collect();
+	let per_thread = shards.len().div_ceil(opts.threads()).max(1);
+	std::thread::scope(|scope| {
+		while !shards.is_empty() {
+			let thread_shards = shards.split_off(shards.len().saturating_sub(per_thread));
+			scope.spawn(move || {
+				for ((dimension, rx, rz), (signs, books)) in thread_shards {
+					let name = format!("{}-r.{}.{}", dimension.name(), rx, rz);
+					write_signs_and_books(directory, &name, signs, books, version, opts);
+				}
+			});
+		}
+	});
+}
+
+// format a position for the text output
+// the dimension is only named outside the overworld so single dimension output stays the same
+fn location(dimension: Dimension, x: i32, y: i32, z: i32) -> String {
+	match dimension {
+		Dimension::Overworld => format!("{},{},{}", x, y, z),
+		_ => format!("{},{},{} in {}", x, y, z, dimension.name()),
+	}
+}
+
+// write signs as markdown with the lines of every sign in a quote
+// formatting codes are removed from json text too since markdown can't show them
+fn write_signs_markdown(file: &mut impl Write, signs: &[ChunkLevelTileEntities], version: &LevelDatDataVersion) {
+	for sign in signs {
+		let record = sign_record(sign, version, LineOptions::default());
+		writeln!(file, "## Sign at {}\n", location(record.dimension, record.x, record.y, record.z)).unwrap();
+		for line in record.lines.iter().chain(&record.back_lines) {
+			writeln!(file, "> {}  ", markdown_escape(&strip_formatting(line))).unwrap();
+		}
+		writeln!(file).unwrap();
+	}
+}
+
+// write every book as a markdown document with the title as a heading and a rule between the pages
+// formatting codes are always removed since markdown can't show them
+fn write_books_markdown(file: &mut impl Write, books: &[BookWithPos]) {
+	for book in books {
+		let record = book_record(book, false);
+		let title = record.title.as_deref().unwrap_or("Untitled");
+		writeln!(file, "## {}\n", markdown_escape(title)).unwrap();
+		if let Some(author) = &record.author {
+			writeln!(file, "*by {}*\n", markdown_escape(author)).unwrap();
+		}
+		writeln!(file, "Found at {}\n", location(record.dimension, record.x, record.y, record.z)).unwrap();
+		for (index, page) in record.pages.iter().enumerate() {
+			if index > 0 {
+				writeln!(file, "---\n").unwrap();
+			}
+			// pages use single newlines which markdown joins into one paragraph without a hard break
+			let lines: Vec<String> = page.lines().map(markdown_escape).collect();
+			writeln!(file, "{}\n", lines.join("  \n")).unwrap();
+		}
+	}
+}
+
+// write a setblock command for every sign, functions run commands without the leading slash
+// the wood type is part of the block state which isn't read so every sign becomes an oak sign
+fn write_signs_mcfunction(file: &mut impl Write, signs: &[ChunkLevelTileEntities], version: &LevelDatDataVersion) {
+	for sign in signs {
+		let front_lines = [&sign.text1, &sign.text2, &sign.text3, &sign.text4].map(|line| line.as_deref().unwrap_or_default());
+		let mut nbt = format!("front_text:{}", sign_side_snbt(front_lines.into_iter(), sign_color(sign).as_deref(), version));
+		if let Some(back_text) = &sign.back_text {
+			let back_lines: Vec<String> = back_text.messages.iter().map(sign_message).collect();
+			nbt.push_str(&format!(",back_text:{}", sign_side_snbt(back_lines.iter().map(String::as_str), back_text.color.as_deref(), version)));
+		}
+		if sign_is_waxed(sign) {
+			nbt.push_str(",is_waxed:1b");
+		}
+		writeln!(file, "execute in minecraft:{} run setblock {} {} {} {}{{{}}}", sign.dimension.name(), sign.x, sign.y, sign.z, sign_block_state(sign), nbt).unwrap();
+	}
+}
+
+// the block of a sign with the orientation read from the chunk
+fn sign_block_state(sign: &ChunkLevelTileEntities) -> String {
+	let hanging = if normalize_id(&sign.id).contains("hanging") { "hanging_" } else { "" };
+	match (&sign.facing, sign.rotation) {
+		(Some(facing), _) => format!("minecraft:oak_wall_{hanging}sign[facing={facing}]"),
+		(None, Some(rotation)) => format!("minecraft:oak_{hanging}sign[rotation={rotation}]"),
+		(None, None) => format!("minecraft:oak_{hanging}sign"),
+	}
+}
+
+// one side of a sign in the 1.20 format with the lines as json strings
+// raw text from old worlds is turned into json strings so it shows up the same
+fn sign_side_snbt<'a>(lines: impl Iterator<Item = &'a str>, color: Option<&str>, version: &LevelDatDataVersion) -> String {
+	let messages: Vec<String> = lines.map(|line| {
+		let json = match serde_json::from_str::<serde_json::Value>(line) {
+			Ok(value) if version.name != "old" => serde_json::to_string(&value).unwrap(),
+			_ => serde_json::to_string(line).unwrap(),
+		};
+		snbt_string(&json)
+	}).collect();
+	match color {
+		Some(color) => format!("{{messages:[{}],color:{}}}", messages.join(","), snbt_string(color)),
+		None => format!("{{messages:[{}]}}", messages.join(",")),
+	}
+}
+
+fn snbt_string(text: &str) -> String {
+	format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+// escape the characters markdown would read as formatting
+// lines starting with - or + would also become list items
+fn markdown_escape(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	if text.starts_with(['-', '+']) {
+		escaped.push('\\');
+	}
+	for character in text.chars() {
+		if "\\`*_[]<>#|~".contains(character) {
+			escaped.push('\\');
+		}
+		escaped.push(character);
+	}
+	escaped
+}
+
+// write containers and their items in the human readable text format
+fn write_containers_text(file: &mut impl Write, containers: &[ContainerWithPos]) {
+	for container in containers {
+		writeln!(file, "========== {} location: {} ==========", container.block, location(container.dimension, container.x, container.y, container.z)).unwrap();
+		for item in &container.items {
+			let slot = item.slot.map(|slot| format!("slot {}: ", slot)).unwrap_or_default();
+			match &item.custom_name {
+				Some(name) => writeln!(file, "{}{} x{} named {}", slot, item.id, item.count, name).unwrap(),
+				None => writeln!(file, "{}{} x{}", slot, item.id, item.count).unwrap(),
+			}
+			for line in &item.lore {
+				writeln!(file, "\tlore: {}", line).unwrap();
+			}
+			for enchantment in &item.enchantments {
+				writeln!(file, "\tenchantment: {} {}", enchantment.id, enchantment.level).unwrap();
+			}
+			for content in &item.contents {
+				writeln!(file, "\tcontains: {} x{}", content.id, content.count).unwrap();
+			}
+		}
+		writeln!(file).unwrap();
+	}
+}
+
+// write signs in the human readable text format
+fn write_signs_text(file: &mut impl Write, signs: Vec<ChunkLevelTileEntities>, version: &LevelDatDataVersion, opts: &Opts) {
+	if opts.flat {
+		write_signs_flat(file, &signs, version, opts);
+		return;
+	}
+
+	// if version is old then the text is raw but if it is newer then it is json
+	// the json is in the format {"text":"text"} with an optional "extra" field
+	// that contains an array of more json objects
+	let mut group = None;
+	for sign in signs {
+		write_group_header(file, opts.group_by, &mut group, sign.dimension, sign.x, sign.z);
+		writeln!(file, "========== sign location: {} ==========", location(sign.dimension, sign.x, sign.y, sign.z)).unwrap();
+
+		// collect the events before the text fields are consumed
+		let mut events = Vec::new();
+		if opts.events && version.name != "old" {
+			for sign_text in parse_sign_lines(&sign) {
+				for click_event in click_events(&sign_text) {
+					events.push(format!("event: {} {}", click_event.action, click_event.value));
+				}
+				for hover_event in hover_events(&sign_text) {
+					if let Some(value) = hover_event.contents.as_ref().or(hover_event.value.as_ref()) {
+						events.push(format!("event: {} {}", hover_event.action, value_text(value)));
+					}
+				}
+			}
+		}
+
+		// print text all text fields, numbered from the top so empty lines can be told apart
+		for (index, text) in sign_lines(&sign, version, opts.line_options()).into_iter().enumerate() {
+			if opts.legacy_format {
+				writeln!(file, "text: {}", text).unwrap();
+			} else {
+				writeln!(file, "line{}: {}", index + 1, text).unwrap();
+			}
+		}
+		for (index, text) in sign_back_lines(&sign, version, opts.line_options()).into_iter().enumerate() {
+			if opts.legacy_format {
+				writeln!(file, "back text: {}", text).unwrap();
+			} else {
+				writeln!(file, "back line{}: {}", index + 1, text).unwrap();
+			}
+		}
+		// undyed signs are black so only dyed ones get a color line
+		if let Some(color) = sign_color(&sign).filter(|color| color != "black") {
+			writeln!(file, "color: {}", color).unwrap();
+		}
+		if let Some(color) = sign.back_text.as_ref().and_then(|back_text| back_text.color.as_ref()).filter(|color| *color != "black") {
+			writeln!(file, "back color: {}", color).unwrap();
+		}
+		if sign_is_waxed(&sign) {
+			writeln!(file, "waxed").unwrap();
+		}
+		if let Some(kind) = sign_kind(&sign) {
+			writeln!(file, "kind: {}", kind.name()).unwrap();
+		}
+		if let Some(rotation) = sign.rotation {
+			writeln!(file, "rotation: {}", rotation).unwrap();
+		}
+		if let Some(facing) = sign.facing {
+			writeln!(file, "facing: {}", facing).unwrap();
+		}
+		if let Some(last_modified) = sign.last_modified {
+			writeln!(file, "last modified: {}", last_modified).unwrap();
+		}
+		for event in events {
+			writeln!(file, "{}", event).unwrap();
+		}
+		writeln!(file, "").unwrap();
+	}
+}
+
+// write the headers of --group-by when a sign or book is the first of its dimension or region
+// group is the dimension and region of the one before it
+fn write_group_header(file: &mut impl Write, group_by: Option<GroupBy>, group: &mut Option<(Dimension, i32, i32)>, dimension: Dimension, x: i32, z: i32) {
+	let group_by = match group_by {
+		Some(group_by) => group_by,
+		None => return,
+	};
+	// regions are 512 blocks wide
+	let (rx, rz) = (x.div_euclid(512), z.div_euclid(512));
+	let new_dimension = group.map(|(group_dimension, _, _)| group_dimension) != Some(dimension);
+	if new_dimension {
+		writeln!(file, "########## {} ##########\n", dimension.name()).unwrap();
+	}
+	if group_by == GroupBy::Region && (new_dimension || group.map(|(_, group_rx, group_rz)| (group_rx, group_rz)) != Some((rx, rz))) {
+		writeln!(file, "---------- region r.{}.{}.mca ----------\n", rx, rz).unwrap();
+	}
+	*group = Some((dimension, rx, rz));
+}
+
+// write every sign on its own line so the output can be searched with grep
+// the back lines of a 1.20 sign come after the front ones behind "back:" so both sides match one grep
+// the dimension is only written outside the overworld like in the other text output
+fn write_signs_flat(file: &mut impl Write, signs: &[ChunkLevelTileEntities], version: &LevelDatDataVersion, opts: &Opts) {
+	for sign in signs {
+		let record = sign_record(sign, version, opts.line_options());
+		let mut position = format!("{} {} {}", record.x, record.y, record.z);
+		if record.dimension != Dimension::Overworld {
+			position.push(' ');
+			position.push_str(record.dimension.name());
+		}
+		let lines: Vec<String> = record.lines.iter().map(|line| flat_field(line)).collect();
+		write!(file, "{} | {}", position, lines.join(" | ")).unwrap();
+		if !record.back_lines.is_empty() {
+			let back_lines: Vec<String> = record.back_lines.iter().map(|line| flat_field(line)).collect();
+			write!(file, " | back: {}", back_lines.join(" | ")).unwrap();
+		}
+		writeln!(file).unwrap();
+	}
+}
+
+// escape the delimiter and line breaks so every sign stays on one line
+fn flat_field(text: &str) -> String {
+	text.replace('\\', "\\\\")
+		.replace('|', "\\|")
+		.replace('\n', "\\n")
+}
+
+// write books in the human readable text format
+fn write_books_text(file: &mut impl Write, books: Vec<BookWithPos>, opts: &Opts) {
+	let mut group = None;
+	for book in books {
+		write_group_header(file, opts.group_by, &mut group, book.dimension, book.x, book.z);
+		// write xyz coordinates
+		writeln!(file, "=========== book location: {} ==========", location(book.dimension, book.x, book.y, book.z)).unwrap();
+
+		if let Some(last_modified) = book.last_modified {
+			writeln!(file, "last modified: {}", last_modified).unwrap();
+		}
+		for copy in &book.copies {
+			writeln!(file, "copy at: {}", location(copy.dimension, copy.x, copy.y, copy.z)).unwrap();
+		}
+		write_book_text(file, book.book, opts.keep_formatting, opts.flatten_books);
+	}
+}
+
+// write the title, author and pages of a book after its header
+fn write_book_text(file: &mut impl Write, book: Book, keep_formatting: bool, flatten: bool) {
+	// print book title, author and text
+	// check if book has title (writable books don't have titles and author)
+	if let Some(title) = book.title {
+		writeln!(file, "title: {}", title).unwrap();
+	} else {
+		writeln!(file, "title: unknown").unwrap();
+	}
+	// check if book has author
+	if let Some(author) = book.author {
+		writeln!(file, "author: {}", author).unwrap();
+	} else {
+		writeln!(file, "author: unknown").unwrap();
+	}
+	// books renamed in an anvil also have a name
+	if let Some(custom_name) = book.custom_name {
+		writeln!(file, "name: {}", custom_name).unwrap();
+	}
+	let pages = book.pages.unwrap();
+
+	writeln!(file, "{}", format!("pages: {}", pages.len()) ).unwrap();
+
+	// flattened books are one block of text for search indexing, every page on its own line
+	if flatten {
+		writeln!(file, "---------- text ----------").unwrap();
+	}
+	let mut page_number = 1;
+	// iterate over all pages
+	for page in pages {
+		if !flatten {
+			writeln!(file, "---------- page {} ----------", page_number).unwrap();
+		}
+		// print page text
+		let page = if keep_formatting { page } else { strip_formatting(&page) };
+		// write page text to file
+		writeln!(file, "{}", page).unwrap();
+		page_number += 1;
+	}
+	writeln!(file, "").unwrap();
+}
+
+// remove formatting codes from a book page or old sign text
+fn strip_formatting(text: &str) -> String {
+	// remove the following formatting codes in upper or lower case so they don't appear in the text
+	/* 
+		§ + k creates randomly changing characters.
+		§ + l creates bold text.
+		§ + m creates strikethrough text.
+		§ + n creates underlined text.
+		§ + o creates italic text.
+		§ + 0 – f (hexadecimal) creates colored text.
+		§ + r resets any of the previous styles so text after it appears normally.
+	*/
+	// a § without a valid code after it is removed as well
+	static FORMATTING_CODE: OnceLock<Regex> = OnceLock::new();
+	let re = FORMATTING_CODE.get_or_init(|| Regex::new(r"(?i)§[0-9a-fk-or]?").unwrap());
+	re.replace_all(text, "").into_owned()
+}
+
+// get the plain text of the four lines of a sign
+// newer versions store json where the text of the extra components is combined, lines that aren't valid json are kept as is
+// old versions store raw text instead of json, both can contain formatting codes
+fn sign_lines(sign: &ChunkLevelTileEntities, version: &LevelDatDataVersion, line_options: LineOptions) -> Vec<String> {
+	let lines = [&sign.text1, &sign.text2, &sign.text3, &sign.text4].into_iter().map(|line| line.as_deref().unwrap_or_default());
+	text_lines(lines, version, line_options)
+}
+
+// the lines on the back of a 1.20 sign, empty when nothing is written there
+fn sign_back_lines(sign: &ChunkLevelTileEntities, version: &LevelDatDataVersion, line_options: LineOptions) -> Vec<String> {
+	let messages: Vec<String> = match &sign.back_text {
+		Some(back_text) => back_text.messages.iter().map(sign_message).collect(),
+		None => return Vec::new(),
+	};
+	let lines = text_lines(messages.iter().map(String::as_str), version, line_options);
+	if lines.iter().all(|line| line.is_empty()) {
+		Vec::new()
+	} else {
+		lines
+	}
+}
+
+// the dye color of the front of a sign, before 1.20 the whole sign has one color
+fn sign_color(sign: &ChunkLevelTileEntities) -> Option<String> {
+	match (&sign.front_text, &sign.color) {
+		(Some(front_text), _) => front_text.color.clone(),
+		(None, Some(fastnbt::Value::String(color))) => Some(color.clone()),
+		_ => None,
+	}
+}
+
+// whether a sign stands, hangs or is on a wall
+// since 1.13 wall and standing signs share the block entity id minecraft:sign so the block state read for the orientation decides
+// some mods and converted worlds name the wall sign in the id
+fn sign_kind(sign: &ChunkLevelTileEntities) -> Option<SignKind> {
+	let id = normalize_id(&sign.id);
+	let wall = if id.contains("wall") {
+		true
+	} else {
+		match (&sign.facing, sign.rotation) {
+			(Some(_), _) => true,
+			(None, Some(_)) => false,
+			(None, None) => return None,
+		}
+	};
+	Some(match (id.contains("hanging"), wall) {
+		(false, false) => SignKind::Standing,
+		(false, true) => SignKind::Wall,
+		(true, false) => SignKind::Hanging,
+		(true, true) => SignKind::WallHanging,
+	})
+}
+
+fn sign_is_waxed(sign: &ChunkLevelTileEntities) -> bool {
+	sign.is_waxed.is_some_and(|is_waxed| is_waxed != 0)
+}
+
+// how the lines of signs are written
+// raw_json only changes the json of newer versions, keep_formatting the text of every version written without it
+#[derive(Clone, Copy, Debug, Default)]
+struct LineOptions {
+	keep_formatting: bool,
+	raw_json: bool,
+}
+
+fn text_lines<'a>(lines: impl Iterator<Item = &'a str>, version: &LevelDatDataVersion, line_options: LineOptions) -> Vec<String> {
+	let version_id = if version.name == "old" { RAW_TEXT_VERSION } else { version.id };
+	lines
+		.map(|line| {
+			if line_options.raw_json && version_id != RAW_TEXT_VERSION {
+				return line.to_string();
+			}
+			// json text can hold formatting codes too, often written as \u00a7 escapes by plugins and editors
+			let text = reconstruct_sign_text(line, version_id);
+			if !line_options.keep_formatting {
+				strip_formatting(&text)
+			} else {
+				text
+			}
+		})
+		.collect()
+}
+
+// get the json of a line on a 1.20 sign
+// since 1.21.5 the text component is stored as nbt which is turned back into json
+fn sign_message(message: &fastnbt::Value) -> String {
+	match message {
+		fastnbt::Value::String(json) => json.clone(),
+		// nbt lists can only hold one type so plain strings next to compounds are wrapped in a compound with an empty key
+		fastnbt::Value::Compound(compound) if compound.len() == 1 && compound.contains_key("") => {
+			serde_json::to_string(&compound[""]).unwrap_or_default()
+		}
+		component => serde_json::to_string(component).unwrap_or_default(),
+	}
+}
+
+// convert a sign to a record for structured output
+fn sign_record(sign: &ChunkLevelTileEntities, version: &LevelDatDataVersion, line_options: LineOptions) -> SignRecord {
+	SignRecord {
+		dimension: sign.dimension,
+		x: sign.x,
+		y: sign.y,
+		z: sign.z,
+		lines: sign_lines(sign, version, line_options),
+		back_lines: sign_back_lines(sign, version, line_options),
+		color: sign_color(sign),
+		back_color: sign.back_text.as_ref().and_then(|back_text| back_text.color.clone()),
+		waxed: sign_is_waxed(sign),
+		kind: sign_kind(sign),
+		rotation: sign.rotation,
+		facing: sign.facing.clone(),
+		last_modified: sign.last_modified,
+		raw_text: broken_lines(sign, version),
+		provenance: sign.chunk_format.map(|chunk_format| Provenance {
+			chunk_format: chunk_format.name().to_string(),
+			text: text_provenance(sign, version, line_options).to_string(),
+			dimension: sign.dimension,
+		}),
+	}
+}
+
+// the lines that look like json but can't be parsed are written as they are stored in lines
+// they are also kept here so they can be told apart from text that only looks broken and sent with a bug report
+fn broken_lines(sign: &ChunkLevelTileEntities, version: &LevelDatDataVersion) -> BTreeMap<String, String> {
+	let mut raw_text = BTreeMap::new();
+	if version.name == "old" {
+		return raw_text;
+	}
+	let front = [&sign.text1, &sign.text2, &sign.text3, &sign.text4].into_iter()
+		.enumerate()
+		.filter_map(|(index, line)| Some((format!("text{}", index + 1), line.clone()?)));
+	let back = sign.back_text.iter()
+		.flat_map(|back_text| back_text.messages.iter().map(sign_message).enumerate())
+		.map(|(index, line)| (format!("back_text{}", index + 1), line));
+	for (name, line) in front.chain(back) {
+		if line_format(&line) == Some(LineFormat::Broken) {
+			raw_text.insert(name, line);
+		}
+	}
+	raw_text
+}
+
+// how the lines of a sign were read, lines that aren't json are shown as they are stored
+fn text_provenance(sign: &ChunkLevelTileEntities, version: &LevelDatDataVersion, line_options: LineOptions) -> &'static str {
+	if version.name == "old" {
+		return "raw";
+	}
+	if line_options.raw_json {
+		return "json as stored";
+	}
+	let all_json = [&sign.text1, &sign.text2, &sign.text3, &sign.text4].into_iter()
+		.flatten()
+		.all(|line| matches!(line_format(line), Some(LineFormat::Json) | None));
+	if all_json { "json" } else { "json with raw fallback" }
+}
+
+// writable books have no title or author so they only add to the books and pages
+fn book_stats(books: &[BookWithPos]) -> BookStats {
+	let authors: HashSet<&str> = books.iter().filter_map(|book| book.book.author.as_deref()).collect();
+	let titles: HashSet<&str> = books.iter().filter_map(|book| book.book.title.as_deref()).collect();
+	BookStats {
+		books: books.len(),
+		authors: authors.len(),
+		titles: titles.len(),
+		pages: books.iter().map(|book| book.book.pages.as_ref().map_or(0, |pages| pages.len())).sum(),
+	}
+}
+
+// keep only the first of the books with the same title, author and text and list where the copies are
+// books are already sorted so the copy that comes first in the output is kept
+fn dedup_books(books: Vec<BookWithPos>) -> Vec<BookWithPos> {
+	let mut unique: Vec<BookWithPos> = Vec::new();
+	let mut index_of_hash: HashMap<u64, usize> = HashMap::new();
+	for book in books {
+		// copies made before and after an update can differ in formatting so only the text is compared
+		let mut hasher = DefaultHasher::new();
+		book.book.title.hash(&mut hasher);
+		book.book.author.hash(&mut hasher);
+		for page in book.book.pages.iter().flatten() {
+			strip_formatting(&component_text(page)).hash(&mut hasher);
+		}
+		match index_of_hash.entry(hasher.finish()) {
+			Entry::Occupied(index) => unique[*index.get()].copies.push(Location {
+				dimension: book.dimension,
+				x: book.x,
+				y: book.y,
+				z: book.z,
+			}),
+			Entry::Vacant(index) => {
+				index.insert(unique.len());
+				unique.push(book);
+			}
+		}
+	}
+	unique
+}
+
+// convert a book to a record for structured output
+fn book_record(book: &BookWithPos, keep_formatting: bool) -> BookRecord {
+	BookRecord {
+		dimension: book.dimension,
+		x: book.x,
+		y: book.y,
+		z: book.z,
+		title: book.book.title.clone(),
+		author: book.book.author.clone(),
+		custom_name: book.book.custom_name.clone(),
+		last_modified: book.last_modified,
+		copies: book.copies.clone(),
+		pages: book_pages_text(&book.book, keep_formatting),
+	}
+}
+
+// pages are json text components since 1.14, only their text is kept
+fn book_pages_text(book: &Book, keep_formatting: bool) -> Vec<String> {
+	book.pages.iter().flatten()
+		.map(|page| {
+			let text = component_text(page);
+			if keep_formatting { text } else { strip_formatting(&text) }
+		})
+		.collect()
+}
+
+// a geojson point at the x and z coordinates of a record, the whole record including y is kept as its properties
+fn point_feature(x: i32, z: i32, record: &impl serde::Serialize) -> Feature {
+	Feature {
+		kind: "Feature".to_string(),
+		geometry: Point {
+			kind: "Point".to_string(),
+			coordinates: [x, z],
+		},
+		properties: serde_json::to_value(record).unwrap(),
+	}
+}
+
+fn feature_collection(features: Vec<Feature>) -> FeatureCollection {
+	FeatureCollection {
+		kind: "FeatureCollection".to_string(),
+		features,
+	}
+}
+
+// write records as a json array
+fn write_json<T: serde::Serialize>(path: impl AsRef<Path>, records: &T, pretty: bool, encoding: OutputEncoding) {
+	let file = EncodedFile::create(path, encoding).unwrap();
+	if pretty {
+		serde_json::to_writer_pretty(file, records).unwrap();
+	} else {
+		serde_json::to_writer(file, records).unwrap();
+	}
+}
+
+// write records as newline delimited json, one record per line
+fn write_json_lines<T: serde::Serialize>(path: impl AsRef<Path>, records: impl Iterator<Item = T>, encoding: OutputEncoding) {
+	let mut file = EncodedFile::create(path, encoding).unwrap();
+	for record in records {
+		serde_json::to_writer(&mut file, &record).unwrap();
+		writeln!(file).unwrap();
+	}
+}
+
+// parse the json of all text fields of a sign
+// lines that aren't valid json are skipped
+fn parse_sign_lines(sign: &ChunkLevelTileEntities) -> Vec<SignText> {
+	[&sign.text1, &sign.text2, &sign.text3, &sign.text4].into_iter()
+		.flatten()
+		.filter_map(|line| serde_json::from_str(line).ok())
+		.collect()
+}
+
+// get the click events of a line including the ones on its extra components
+fn click_events(sign_text: &SignText) -> Vec<&ClickEvent> {
+	let mut events: Vec<&ClickEvent> = sign_text.click_event.iter().collect();
+	if let Some(extra) = &sign_text.extra {
+		events.extend(extra.iter().filter_map(|extra| extra.click_event.as_ref()));
+	}
+	events
+}
+
+// get the hover events of a line including the ones on its extra components
+fn hover_events(sign_text: &SignText) -> Vec<&HoverEvent> {
+	let mut events: Vec<&HoverEvent> = sign_text.hover_event.iter().collect();
+	if let Some(extra) = &sign_text.extra {
+		events.extend(extra.iter().filter_map(|extra| extra.hover_event.as_ref()));
+	}
+	events
+}
+
+// get the commands a sign runs when clicked
+// the command can be on the line itself or on any of its extra components
+fn sign_commands(sign: &ChunkLevelTileEntities) -> Vec<String> {
+	let mut commands = Vec::new();
+	for sign_text in parse_sign_lines(sign) {
+		for click_event in click_events(&sign_text) {
+			if click_event.action == "run_command" {
+				commands.push(click_event.value.clone());
+			}
+		}
+	}
+	commands
+}
+
+// get the region coordinates from a region file name like r.-1.2.mca
+// worlds from beta 1.3 to 1.1 use the mcregion format in r.-1.2.mcr files which have the same layout
+// backups can also compress the whole file into r.-1.2.mca.gz or r.-1.2.mca.zst
+// the extension can be uppercase when the world was copied by some tools or from a case insensitive filesystem
+fn region_coordinates(file_name: &str) -> Option<(i32, i32)> {
+	// check if file name matches regex, it is compiled once since every file of a region folder is checked
+	static REGION_FILE_NAME: OnceLock<Regex> = OnceLock::new();
+	let re = REGION_FILE_NAME.get_or_init(|| Regex::new(r"r\.(?P<rx>-?\d+)\.(?P<ry>-?\d+)\.(?i:mcr|mca(\.gz|\.zst)?)$").expect("invalid regex"));
+	let caps = re.captures(file_name)?;
+
+	// convert to i32
+	let rx = caps.name("rx").unwrap().as_str().parse::<i32>().ok()?;
+	let ry = caps.name("ry").unwrap().as_str().parse::<i32>().ok()?;
+	Some((rx, ry))
+}
+
+// the game converts a mcregion world to anvil when it is opened in 1.2 or later but leaves the .mcr files behind
+// those hold the world from before the conversion so they are only read when there is no .mca file of the region
+fn replaced_by_anvil(file_path: &Path) -> bool {
+	let file_name = match file_path.file_name().and_then(|name| name.to_str()) {
+		Some(file_name) => file_name,
+		None => return false,
+	};
+	match region_coordinates(file_name) {
+		Some((rx, rz)) if file_name.to_ascii_lowercase().contains(".mcr") => file_path.with_file_name(format!("r.{}.{}.mca", rx, rz)).exists(),
+		_ => false,
+	}
+}
+
+// find the world in a .tar.gz archive and return its root folder and the contents of its level.dat and level.dat_old
+fn find_level_dat_in_archive(archive_path: &Path) -> Option<(PathBuf, LevelDatFiles)> {
+	let archive_file = open_shared(archive_path).ok()?;
+	let mut archive = tar::Archive::new(GzDecoder::new(archive_file));
+	let mut root = None;
+	let mut level_dats = Vec::new();
+	for entry in archive.entries().ok()? {
+		// a truncated archive can still have level.dat in the part that was written
+		let mut entry = match entry {
+			Ok(entry) => entry,
+			Err(_e) => break,
+		};
+		let entry_path = match entry.path() {
+			Ok(entry_path) => entry_path.into_owned(),
+			Err(_e) => continue,
+		};
+		let name = match LEVEL_DAT_NAMES.iter().find(|name| entry_path.file_name().and_then(|name| name.to_str()) == Some(**name)) {
+			Some(name) => *name,
+			None => continue,
+		};
+		// the first world in the archive is used, backups can contain more than one
+		let parent = entry_path.parent().map(Path::to_path_buf).unwrap_or_default();
+		if root.get_or_insert_with(|| parent.clone()) != &parent {
+			continue;
+		}
+		let mut data = Vec::new();
+		entry.read_to_end(&mut data).ok()?;
+		level_dats.push((name, data));
+		if level_dats.len() == LEVEL_DAT_NAMES.len() {
+			break;
+		}
+	}
+	// level.dat is tried first wherever it is in the archive
+	level_dats.sort_by_key(|(name, _)| LEVEL_DAT_NAMES.iter().position(|level_dat| level_dat == name));
+	Some((root?, level_dats))
+}
+
+// level.dat_old is the copy of level.dat from before the last save
+const LEVEL_DAT_NAMES: [&str; 2] = ["level.dat", "level.dat_old"];
+// the names and contents of the level.dat files of a world
+type LevelDatFiles = Vec<(&'static str, Vec<u8>)>;
+
+// parse the first of the level.dat files that can be read
+// level.dat can be missing or cut off when the game crashed while saving, level.dat_old is used then
+fn parse_level_dat(level_dats: Vec<(&str, std::io::Result<Vec<u8>>)>) -> Option<LevelDat> {
+	if level_dats.is_empty() {
+		error!("save version does not exist");
+		return None;
+	}
+	for (name, data) in &level_dats {
+		let data = match data {
+			Ok(data) => data,
+			Err(e) => {
+				warn!("failed to open {}: {}{}", name, e, sharing_violation_hint(e));
+				continue;
+			}
+		};
+		match fastnbt::from_reader(GzDecoder::new(&data[..])) {
+			Ok(level_dat) => {
+				if *name != LEVEL_DAT_NAMES[0] {
+					warn!("level.dat is missing or broken, using {} from before the last save", name);
+				}
+				return Some(level_dat);
+			}
+			Err(e) => warn!("failed to read {}: {}", name, e),
+		}
+	}
+	error!("no readable level.dat, try --force-version to read the regions anyway");
+	None
+}
+
+fn extract_signs_from_mca_file(file_path:PathBuf, version:LevelDatDataVersion, options:&ExtractOptions) -> RegionResult {
+	let (rx, ry) = match wanted_region_coordinates(&file_path, options) {
+		Some(coordinates) => coordinates,
+		None => return RegionResult::default(),
+	};
+
+	// open file
+	let region_file = match open_shared(&file_path) {
+		Ok(file) => file,
+		Err(e) => {
+			warn!("failed to open {}: {}{}", file_path.display(), e, sharing_violation_hint(&e));
+			return with_region_info(RegionResult::default(), &file_path, 0, options.dimension, Some(e.to_string()));
+		}
+	};
+	let size = region_file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+	extract_signs_from_region_data(region_file, &file_path, size, rx, ry, version, options)
+}
+
+// the coordinates of a region file, or none if it isn't a region file or none of its chunks are wanted
+fn wanted_region_coordinates(file_path: &Path, options: &ExtractOptions) -> Option<(i32, i32)> {
+	let (rx, ry) = region_coordinates(file_path.file_name()?.to_str()?)?;
+	// regions without any of the chunks from --chunks don't have to be opened
+	options.region_wanted(rx, ry).then_some((rx, ry))
+}
+
+// read a whole region file into memory for --threads-io so the parse threads never wait for the disk
+type ReadRegion = (PathBuf, i32, i32, std::io::Result<Vec<u8>>);
+
+fn read_region_file(file_path: PathBuf, options: &ExtractOptions) -> Option<ReadRegion> {
+	let (rx, ry) = wanted_region_coordinates(&file_path, options)?;
+	let data = time_phase(Phase::Reading, || {
+		let mut data = Vec::new();
+		open_shared(&file_path)?.read_to_end(&mut data)?;
+		Ok(data)
+	});
+	Some((file_path, rx, ry, data))
+}
+
+// extract the signs of a region file that is opened or already read into memory
+fn extract_signs_from_region_data(mut region_file: impl Read + Seek, file_path: &Path, size: u64, rx: i32, ry: i32, version: LevelDatDataVersion, options: &ExtractOptions) -> RegionResult {
+	let file_name = file_path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+	if let Some(decompressed) = decompress_region_file(&file_name, &mut region_file) {
+		return match decompressed {
+			Ok(data) => with_region_info(extract_signs_from_mca(std::io::Cursor::new(data), rx, ry, version, options), file_path, size, options.dimension, None),
+			Err(e) => {
+				warn!("failed to decompress {}: {}", file_path.display(), e);
+				with_region_info(RegionResult::default(), file_path, size, options.dimension, Some(e.to_string()))
+			}
+		};
+	}
+	let mut result = extract_signs_from_mca(region_file, rx, ry, version.clone(), options);
+	if options.live {
+		result = retry_failed_chunks(file_path, rx, ry, &version, options, result);
+	}
+	with_region_info(result, file_path, size, options.dimension, None)
+}
+
+// how often chunks that failed with --live are read again and how long to wait before the first try
+const LIVE_RETRIES: u32 = 3;
+const LIVE_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+// read the chunks that failed again for --live
+// a server that saves a chunk while it is read leaves it half written for a moment, so waiting a bit usually fixes it
+fn retry_failed_chunks(file_path: &Path, rx: i32, ry: i32, version: &LevelDatDataVersion, options: &ExtractOptions, mut result: RegionResult) -> RegionResult {
+	for attempt in 1..=LIVE_RETRIES {
+		if result.failed_chunk_positions.is_empty() {
+			return result;
+		}
+		std::thread::sleep(LIVE_RETRY_DELAY * attempt);
+		let region_file = match open_shared(file_path) {
+			Ok(file) => file,
+			Err(e) => {
+				warn!("failed to open {} again: {}", file_path.display(), e);
+				break;
+			}
+		};
+		// a file that is being rewritten can be empty for a moment, none of the chunks could be read from it
+		if region_file.metadata().map(|metadata| metadata.len()).unwrap_or(0) == 0 {
+			continue;
+		}
+		let retry_options = ExtractOptions { chunks: std::mem::take(&mut result.failed_chunk_positions), ..options.clone() };
+		let retried = extract_signs_from_mca(region_file, rx, ry, version.clone(), &retry_options);
+		// without the header none of the chunks were read, they are all tried again the next time
+		if retried.errors.iter().any(|error| matches!(error.kind, ReadErrorKind::Header | ReadErrorKind::File)) {
+			result.failed_chunk_positions = retry_options.chunks;
+			continue;
+		}
+		// the chunks were already counted as chunks with data the first time
+		result.failed_chunks -= retry_options.chunks.len() - retried.failed_chunks;
+		result.failed_chunk_positions = retried.failed_chunk_positions;
+		// the chunks that were read again report their errors again if they still have any
+		result.errors.retain(|error| error.chunk_x.zip(error.chunk_z).is_none_or(|position| !retry_options.chunks.contains(&position)));
+		result.errors.extend(retried.errors);
+		result.signs.extend(retried.signs);
+		result.books.extend(retried.books);
+		result.commands.extend(retried.commands);
+		result.chunks.extend(retried.chunks);
+		result.raw_signs.extend(retried.raw_signs);
+		result.structures.extend(retried.structures);
+		result.containers.extend(retried.containers);
+		for (id, count) in retried.block_entity_ids {
+			*result.block_entity_ids.entry(id).or_default() += count;
+		}
+	}
+	for (chunk_x, chunk_z) in &result.failed_chunk_positions {
+		warn!("chunk {}, {} in {} couldn't be read after {} more tries", chunk_x, chunk_z, file_path.display(), LIVE_RETRIES);
+	}
+	result
+}
+
+// add the region file to the manifest of the files that were read
+// error is set if the file couldn't be read at all
+fn with_region_info(mut result: RegionResult, file_path: &Path, size: u64, dimension: Dimension, error: Option<String>) -> RegionResult {
+	// only regions with something in them are logged by default so the log shows where to look
+	if !result.signs.is_empty() || !result.books.is_empty() {
+		info!("{}: {} signs, {} books", file_path.display(), result.signs.len(), result.books.len());
+	}
+	let region = file_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+	for chunk_error in &mut result.errors {
+		chunk_error.region = region.clone();
+	}
+	if let Some(error) = &error {
+		result.errors.push(ReadError { region, ..chunk_error(dimension, None, ReadErrorKind::File, error.clone()) });
+	}
+	result.regions.push(RegionInfo {
+		dimension,
+		file: file_path.display().to_string(),
+		size,
+		chunks: result.chunks_with_data,
+		failed_chunks: result.failed_chunks,
+		error,
+	});
+	result
+}
+
+// read a region file that was compressed as a whole by a backup tool into memory
+// returns None for normal region files which are read in place
+fn decompress_region_file(file_name: &str, compressed: impl Read) -> Option<std::io::Result<Vec<u8>>> {
+	// the file is read while it is decompressed so this counts as decompressing
+	let _decompressing = PhaseTimer::start(Phase::Decompressing);
+	let mut data = Vec::new();
+	let file_name = file_name.to_lowercase();
+	let read = if file_name.ends_with(".mca.gz") {
+		GzDecoder::new(compressed).read_to_end(&mut data)
+	} else if file_name.ends_with(".mca.zst") {
+		zstd::stream::read::Decoder::new(compressed).and_then(|mut decoder| decoder.read_to_end(&mut data))
+	} else {
+		return None;
+	};
+	Some(read.map(|_| data))
+}
+
+// extract signs and books from a region file
+// it can be read from disk, from memory or from an archive, the region coordinates come from the file name
+fn extract_signs_from_mca(mut region_file: impl Read + Seek, rx: i32, ry: i32, version:LevelDatDataVersion, options:&ExtractOptions) -> RegionResult {
+	let mut result = RegionResult::default();
+
+	// print region coordinates, logs go to std err to not mess up the output when piping to a file
+	debug!("---------- reading region: {}, {} ----------", rx, ry);
+
+	// check if file is not empty/corrupted
+	let length = region_file.seek(std::io::SeekFrom::End(0)).expect("failed to seek");
+	if length == 0 {
+		return result;
+	}
+
+	let chunk_format = chunk_format_of(&version);
+	// region files are also written when a single chunk changes so --modified-since is checked for every chunk as well
+	let wanted = |x, z, timestamp| {
+		options.chunk_wanted(rx * 32 + x, ry * 32 + z) && options.modified_since.is_none_or(|since| timestamp >= since)
+	};
+	let failed_to_read = for_each_chunk(&mut region_file, wanted, |x, z, timestamp, buf| {
+		result.chunks_with_data += 1;
+
+		// remember how much was found before this chunk to count what it contains
+		let signs_before = result.signs.len();
+		let books_before = result.books.len();
+
+		if options.dump_raw_signs {
+			result.raw_signs.extend(time_phase(Phase::Parsing, || raw_signs_of_chunk(buf, chunk_format, options.dimension)));
+		}
+
+		// snapshots can be in between two chunk formats so their chunks are read like with --ignore-version
+		let read = |buf| if options.entities {
+			read_entity_chunk(buf).map(|entities| (chunk_format, (Vec::new(), entities)))
+		} else if options.ignore_version || version.snapshot {
+			read_chunk_in_any_format(buf)
+		} else {
+			read_chunk(buf, chunk_format).map(|chunk| (chunk_format, chunk))
+		};
+		let (used_format, (block_entities, entities)) = match time_phase(Phase::Parsing, || read(buf)) {
+			Ok(chunk) => chunk,
+			Err(e) => {
+				// print error and chunk coordinates
+				warn!("failed to read nbt in chunk: {}, {} with error {}", rx * 32 + x, ry * 32 + z, e);
+				result.failed_chunks += 1;
+				result.failed_chunk_positions.push((rx * 32 + x, ry * 32 + z));
+				result.errors.push(chunk_error(options.dimension, Some((rx * 32 + x, ry * 32 + z)), ReadErrorKind::Nbt, e.to_string()));
+				return;
+			}
+		};
+		extract_from_chunk(block_entities, entities, options, &mut result);
+		for sign in &mut result.signs[signs_before..] {
+			sign.chunk_format = Some(used_format);
+		}
+
+		if options.timestamps {
+			for sign in &mut result.signs[signs_before..] {
+				sign.last_modified = Some(timestamp);
+			}
+			for book in &mut result.books[books_before..] {
+				book.last_modified = Some(timestamp);
+			}
+		}
+
+		// only look at the block states of chunks that have signs since reading them is expensive
+		if result.signs.len() > signs_before {
+			let orientations = time_phase(Phase::Parsing, || read_sign_orientations(buf, &version, &mut result.signs[signs_before..]));
+			if let Err(e) = orientations {
+				warn!("failed to read block states in chunk: {}, {} with error {}", rx * 32 + x, ry * 32 + z, e);
+				result.errors.push(chunk_error(options.dimension, Some((rx * 32 + x, ry * 32 + z)), ReadErrorKind::BlockStates, e.to_string()));
+			}
+		}
+
+		result.chunks.push(ChunkCount {
+			dimension: options.dimension,
+			chunk_x: rx * 32 + x,
+			chunk_z: ry * 32 + z,
+			signs: result.signs.len() - signs_before,
+			books: result.books.len() - books_before,
+		});
+	});
+	// a broken header isn't a chunk so it doesn't count as one
+	for failed in failed_to_read {
+		let position = failed.position.map(|(x, z)| (rx * 32 + x, ry * 32 + z));
+		if let Some(position) = position {
+			result.chunks_with_data += 1;
+			result.failed_chunks += 1;
+			result.failed_chunk_positions.push(position);
+		}
+		result.errors.push(chunk_error(options.dimension, position, failed.kind, failed.message));
+	}
+	result
+}
+
+// an error of a chunk for --error-report, the region is filled in by with_region_info since it isn't known while chunks are read
+fn chunk_error(dimension: Dimension, position: Option<(i32, i32)>, kind: ReadErrorKind, message: String) -> ReadError {
+	ReadError {
+		dimension,
+		region: String::new(),
+		chunk_x: position.map(|(x, _)| x),
+		chunk_z: position.map(|(_, z)| z),
+		kind,
+		message,
+	}
+}
+
+// poi types of the blocks villagers use as workstations
+const WORKSTATIONS: [&str; 13] = [
+	"minecraft:armorer",
+	"minecraft:butcher",
+	"minecraft:cartographer",
+	"minecraft:cleric",
+	"minecraft:farmer",
+	"minecraft:fisherman",
+	"minecraft:fletcher",
+	"minecraft:leatherworker",
+	"minecraft:librarian",
+	"minecraft:mason",
+	"minecraft:shepherd",
+	"minecraft:toolsmith",
+	"minecraft:weaponsmith",
+];
+
+// extract villager workstations from a poi region file
+fn extract_workstations_from_mca(file_path:PathBuf, dimension: Dimension) -> Vec<PoiWithPos> {
+	let mut workstations:Vec<PoiWithPos> = Vec::new();
+
+	// skip anything that isn't a region file
+	if !file_path.extension().and_then(|extension| extension.to_str()).is_some_and(|extension| extension.eq_ignore_ascii_case("mca")) {
+		return workstations;
+	}
+
+	let mut region_file = match open_shared(&file_path) {
+		Ok(file) => file,
+		Err(e) => {
+			warn!("failed to open {}: {}{}", file_path.display(), e, sharing_violation_hint(&e));
+			return workstations;
+		}
+	};
+
+	for_each_chunk(&mut region_file, |_x, _z, _timestamp| true, |_x, _z, _timestamp, buf| {
+		let nbt_data: PoiChunk = match time_phase(Phase::Parsing, || fastnbt::from_bytes(buf)) {
+			Ok(nbt_data) => nbt_data,
+			Err(_e) => return,
+		};
+		for section in nbt_data.sections.into_values() {
+			for record in section.records {
+				if WORKSTATIONS.contains(&record.poi_type.as_str()) && record.pos.len() >= 3 {
+					workstations.push(PoiWithPos {
+						dimension,
+						x: record.pos[0],
+						y: record.pos[1],
+						z: record.pos[2],
+						poi_type: record.poi_type,
+					});
+				}
+			}
+		}
+	});
+	workstations
+}
+
+// a chunk that for_each_chunk couldn't read, the position is inside the region
+// without a position the region header couldn't be read so none of the chunks were
+struct FailedChunk {
+	position: Option<(i32, i32)>,
+	kind: ReadErrorKind,
+	message: String,
+}
+
+// the two 4KiB tables at the start of a region file, indexed by x + z * 32 of a chunk inside the region
+struct RegionHeader {
+	// sector offset of the chunk in the upper 3 bytes and its number of 4KiB sectors in the lowest byte
+	locations: [u32; 1024],
+	// unix time the chunk was last saved
+	timestamps: [u32; 1024],
+}
+
+fn read_region_header(region_file: &mut (impl Read + Seek)) -> std::io::Result<RegionHeader> {
+	region_file.seek(std::io::SeekFrom::Start(0))?;
+	let mut header = [0; 8192];
+	region_file.read_exact(&mut header)?;
+	let entry = |index: usize| u32::from_be_bytes([header[index * 4], header[index * 4 + 1], header[index * 4 + 2], header[index * 4 + 3]]);
+	Ok(RegionHeader {
+		locations: std::array::from_fn(entry),
+		timestamps: std::array::from_fn(|index| entry(1024 + index)),
+	})
+}
+
+// read all chunks of a region file and pass their decompressed nbt to the handler
+// along with the position of the chunk inside the region and the unix time it was last saved
+// chunks that aren't wanted are skipped before they are read, wanted gets the same position and time
+// returns the chunks that couldn't be read or decompressed
+fn for_each_chunk(region_file: &mut (impl Read + Seek), wanted: impl Fn(i32, i32, u32) -> bool, mut handle_chunk: impl FnMut(i32, i32, u32, &[u8])) -> Vec<FailedChunk> {
+	let mut failed = Vec::new();
+	let header = match time_phase(Phase::Reading, || read_region_header(region_file)) {
+		Ok(header) => header,
+		Err(e) => {
+			warn!("failed to read region header: {}", e);
+			failed.push(FailedChunk { position: None, kind: ReadErrorKind::Header, message: e.to_string() });
+			return failed;
+		}
+	};
+	for x in 0..32 {
+		for z in 0..32 {
+			let index = (x + z * 32) as usize;
+			let timestamp = header.timestamps[index];
+			if !wanted(x, z, timestamp) {
+				continue;
+			}
+
+			// first 3 bytes are offset
+			// last byte is number of 4KiB sectors
+			let offset = header.locations[index] >> 8;
+			let sectors = header.locations[index] & 0xFF;
+
+			// check if chunk is present
+			if sectors == 0 {
+				continue;
+			}
+
+			let reading = PhaseTimer::start(Phase::Reading);
+
+			// seek to chunk
+			let chunk_offset = offset as u64 * 4096;
+			region_file.seek(std::io::SeekFrom::Start(chunk_offset)).expect("failed to seek");
+
+			// read chunk length of remaining chunk bytes
+			// the file can be shorter than the header says while a server is saving it
+			let mut length = [0; 4];
+			if let Err(e) = region_file.read_exact(&mut length) {
+				warn!("failed to read length of chunk {}, {}: {}", x, z, e);
+				failed.push(FailedChunk { position: Some((x, z)), kind: ReadErrorKind::Io, message: e.to_string() });
+				continue;
+			}
+
+			// convert from big endian
+			let length = u32::from_be_bytes(length);
+
+			// the length counts the compression type byte and has to fit in the sectors of the chunk
+			// a corrupt length would otherwise underflow or allocate far more memory than the file has
+			if length == 0 || length as u64 + 4 > sectors as u64 * 4096 {
+				warn!("invalid length {} for chunk {}, {} in region spanning {} sectors", length, x, z, sectors);
+				failed.push(FailedChunk { position: Some((x, z)), kind: ReadErrorKind::InvalidLength, message: format!("invalid length {} in {} sectors", length, sectors) });
+				continue;
+			}
+
+			// get compression type (5th byte)
+			// 1 = gzip
+			// 2 = zlib
+			// 3 = uncompressed
+			let mut compression_type = [0; 1];
+			if let Err(e) = region_file.read_exact(&mut compression_type) {
+				warn!("failed to read compression type of chunk {}, {}: {}", x, z, e);
+				failed.push(FailedChunk { position: Some((x, z)), kind: ReadErrorKind::Io, message: e.to_string() });
+				continue;
+			}
+
+			// only gzip and zlib compressed chunks can be read
+			if compression_type[0] != 1 && compression_type[0] != 2 {
+				warn!("unsupported compression type: {}", compression_type[0]);
+				failed.push(FailedChunk { position: Some((x, z)), kind: ReadErrorKind::UnsupportedCompression, message: format!("unsupported compression type {}", compression_type[0]) });
+				continue;
+			}
+
+			let mut chunk = vec![0; (length-1) as usize];
+			if let Err(e) = region_file.read_exact(&mut chunk) {
+				warn!("failed to read chunk {}, {}: {}", x, z, e);
+				failed.push(FailedChunk { position: Some((x, z)), kind: ReadErrorKind::Io, message: e.to_string() });
+				continue;
+			}
+			drop(reading);
+
+			let buf = match time_phase(Phase::Decompressing, || decompress_chunk(&chunk, compression_type[0] == 1)) {
+				Ok(buf) => buf,
+				Err(e) => {
+					warn!("failed to decompress chunk {}, {} in region: {}", x, z, e);
+					failed.push(FailedChunk { position: Some((x, z)), kind: ReadErrorKind::Decompression, message: e.to_string() });
+					continue;
+				}
+			};
+
+			handle_chunk(x, z, timestamp, &buf);
+		}
+	}
+	failed
+}
+
+// decompress a chunk with the compression its header names
+// some third party editors write the other compression than the header says so that is tried when the first one fails
+fn decompress_chunk(data: &[u8], gzip: bool) -> std::io::Result<Vec<u8>> {
+	let decompress = |gzip: bool| {
+		let mut buf = vec![];
+		if gzip {
+			GzDecoder::new(data).read_to_end(&mut buf)?;
+		} else {
+			ZlibDecoder::new(data).read_to_end(&mut buf)?;
+		}
+		Ok(buf)
+	};
+	decompress(gzip).or_else(|e: std::io::Error| {
+		debug!("failed to decompress chunk as {}, trying {}: {}", if gzip { "gzip" } else { "zlib" }, if gzip { "zlib" } else { "gzip" }, e);
+		decompress(!gzip).map_err(|_| e)
+	})
+}
+
+// extract signs and books from a cubic chunks region file
+// every region holds 16x16x16 cubes, the header has a 4 byte entry per cube
+// with the sector offset in the upper 3 bytes and the number of sectors in the lowest byte
+// sectors are 512 bytes and every cube starts with a 4 byte length followed by gzip compressed nbt
+// the cubes use the same format as pre 1.17 chunks
+fn extract_signs_from_3dr_file(file_path:PathBuf, options:&ExtractOptions) -> RegionResult {
+	let file_name = file_path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+
+	// check if file name matches regex
+	// cubic chunks names its regions x.y.z.3dr, allow an r. prefix like vanilla regions
+	let re: Regex = Regex::new(r"^(r\.)?(?P<rx>-?\d+)\.(?P<ry>-?\d+)\.(?P<rz>-?\d+)\.(?i:3dr)$").expect("invalid regex");
+	let caps = match re.captures(&file_name){
+		Some(caps) => caps,
+		None => return RegionResult::default(),
+	};
+	debug!("---------- reading cube region: {}, {}, {} ----------", &caps["rx"], &caps["ry"], &caps["rz"]);
+
+	let region_file = match open_shared(&file_path) {
+		Ok(file) => file,
+		Err(e) => {
+			warn!("failed to open {}: {}{}", file_path.display(), e, sharing_violation_hint(&e));
+			return with_region_info(RegionResult::default(), &file_path, 0, options.dimension, Some(e.to_string()));
+		}
+	};
+	let size = region_file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+	with_region_info(extract_signs_from_3dr(region_file, &file_name, options), &file_path, size, options.dimension, None)
+}
+
+// extract signs and books from a cubic chunks region file
+// the file name is only used to tell which file a broken cube is in
+fn extract_signs_from_3dr(mut region_file: impl Read, file_name: &str, options:&ExtractOptions) -> RegionResult {
+	let mut result = RegionResult::default();
+
+	let mut data = Vec::new();
+	time_phase(Phase::Reading, || region_file.read_to_end(&mut data)).expect("failed to read file");
+
+	for index in 0..16 * 16 * 16 {
+		// read header entry
+		let entry = match data.get(index * 4..index * 4 + 4) {
+			Some(entry) => u32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]]),
+			None => break,
+		};
+		let offset = (entry >> 8) as usize * 512;
+		let sectors = entry & 0xFF;
+
+		// check if cube is present
+		if sectors == 0 {
+			continue;
+		}
+		result.chunks_with_data += 1;
+
+		// read cube length and data
+		let length = match data.get(offset..offset + 4) {
+			Some(length) => u32::from_be_bytes([length[0], length[1], length[2], length[3]]) as usize,
+			None => continue,
+		};
+		let cube = match data.get(offset + 4..offset + 4 + length) {
+			Some(cube) => cube,
+			None => {
+				warn!("cube {} in {} is truncated", index, file_name);
+				result.failed_chunks += 1;
+				result.errors.push(chunk_error(options.dimension, None, ReadErrorKind::Io, format!("cube {} is truncated", index)));
+				continue;
+			}
+		};
+
+		// cubes are written gzip compressed, fall back to zlib for other writers
+		let buf = match time_phase(Phase::Decompressing, || decompress_chunk(cube, cube.starts_with(&[0x1f, 0x8b]))) {
+			Ok(buf) => buf,
+			Err(e) => {
+				warn!("failed to decompress cube {} in {}: {}", index, file_name, e);
+				result.failed_chunks += 1;
+				result.errors.push(chunk_error(options.dimension, None, ReadErrorKind::Decompression, format!("cube {}: {}", index, e)));
+				continue;
+			}
+		};
+
+		// cubes store their block entities like pre 1.17 chunks
+		if options.dump_raw_signs {
+			result.raw_signs.extend(time_phase(Phase::Parsing, || raw_signs_of_chunk(&buf, ChunkFormat::Legacy, options.dimension)));
+		}
+
+		let nbt_data: Chunk = match time_phase(Phase::Parsing, || fastnbt::from_bytes(buf.as_slice())) {
+			Ok(nbt_data) => nbt_data,
+			Err(e) => {
+				warn!("failed to read nbt in cube {} in {} with error {}", index, file_name, e);
+				result.failed_chunks += 1;
+				result.errors.push(chunk_error(options.dimension, None, ReadErrorKind::Nbt, format!("cube {}: {}", index, e)));
+				continue;
+			}
+		};
+		let signs_before = result.signs.len();
+		extract_from_chunk(nbt_data.level.tile_entities, nbt_data.level.entities, options, &mut result);
+		for sign in &mut result.signs[signs_before..] {
+			sign.chunk_format = Some(ChunkFormat::Legacy);
+		}
+	}
+	result
+}
+
+// read the entities of a chunk from the entities folder
+// vanilla keeps them at the root but some tools wrap them in Level like the chunks before 1.18
+fn read_entity_chunk(buf: &[u8]) -> fastnbt::error::Result<Vec<Entity>> {
+	let nbt_data: EntityChunk = fastnbt::from_bytes(buf)?;
+	Ok(match (nbt_data.entities, nbt_data.level) {
+		(Some(entities), _) => entities,
+		(None, Some(level)) => level.entities,
+		(None, None) => Vec::new(),
+	})
+}
+
+// the block entities and entities of a chunk
+type ChunkContents = (Vec<ChunkLevelTileEntities>, Vec<Entity>);
+
+// deserialize a chunk into its block entities and entities
+// chunks from 1.17 on have no entities since those are stored in the entities folder
+fn read_chunk(buf: &[u8], chunk_format: ChunkFormat) -> fastnbt::error::Result<ChunkContents> {
+	read_whole_chunk(buf, chunk_format).or_else(|e| {
+		// modded block entities can reuse field names like Items with another type which fails the whole chunk
+		// so read the block entities and entities one at a time and only skip the ones that don't fit
+		let (raw_block_entities, raw_entities) = raw_chunk_contents(buf, chunk_format).map_err(|_| e)?;
+		Ok((read_one_at_a_time(raw_block_entities, "block entity"), read_one_at_a_time(raw_entities, "entity")))
+	})
+}
+
+// deserialize raw block entities or entities and skip the ones that don't fit with a warning
+fn read_one_at_a_time<T: serde::de::DeserializeOwned>(raw_values: Vec<fastnbt::Value>, kind: &str) -> Vec<T> {
+	let mut values = Vec::new();
+	for raw_value in raw_values {
+		match fastnbt::to_bytes(&raw_value).and_then(|bytes| fastnbt::from_bytes(&bytes)) {
+			Ok(value) => values.push(value),
+			Err(e) => {
+				let id = match compound_field(&raw_value, "id") {
+					Some(fastnbt::Value::String(id)) => id.as_str(),
+					_ => "unknown",
+				};
+				warn!("skipped {} {} that couldn't be read: {}", kind, id, e);
+			}
+		}
+	}
+	values
+}
+
+// try the chunk formats from new to old for --ignore-version and use the first one that can be read
+// only if none of them fit are the block entities read one at a time
+fn read_chunk_in_any_format(buf: &[u8]) -> fastnbt::error::Result<(ChunkFormat, ChunkContents)> {
+	for chunk_format in [ChunkFormat::V1_18, ChunkFormat::V1_17, ChunkFormat::Legacy] {
+		if let Ok(chunk) = read_whole_chunk(buf, chunk_format) {
+			return Ok((chunk_format, chunk));
+		}
+	}
+	read_chunk(buf, ChunkFormat::V1_18).map(|chunk| (ChunkFormat::V1_18, chunk))
+}
+
+fn read_whole_chunk(buf: &[u8], chunk_format: ChunkFormat) -> fastnbt::error::Result<ChunkContents> {
+	match chunk_format {
+		ChunkFormat::V1_18 => {
+			let nbt_data: Chunk1_18 = fastnbt::from_bytes(buf)?;
+			// fall back to the old location for chunks that weren't converted yet
+			Ok(match (nbt_data.block_entities, nbt_data.level) {
+				(Some(block_entities), _) => (block_entities, Vec::new()),
+				(None, Some(level)) => (level.tile_entities, level.entities),
+				(None, None) => (Vec::new(), Vec::new()),
+			})
+		}
+		ChunkFormat::V1_17 => {
+			let nbt_data: Chunk1_17 = fastnbt::from_bytes(buf)?;
+			Ok((nbt_data.level.block_entities, Vec::new()))
+		}
+		//todo support version upgraded from/on 1.7 and below
+		ChunkFormat::Legacy => {
+			let nbt_data: Chunk = fastnbt::from_bytes(buf)?;
+			Ok((nbt_data.level.tile_entities, nbt_data.level.entities))
+		}
+	}
+}
+
+// find the signs in a chunk without deserializing them into the sign struct
+// only the block entities and entities are read as raw nbt, the block states of the chunk are skipped
+fn raw_block_entities(buf: &[u8], chunk_format: ChunkFormat) -> fastnbt::error::Result<Vec<fastnbt::Value>> {
+	raw_chunk_contents(buf, chunk_format).map(|(block_entities, _entities)| block_entities)
+}
+
+// the block entities and entities of a chunk as raw nbt, found where read_whole_chunk looks for them
+fn raw_chunk_contents(buf: &[u8], chunk_format: ChunkFormat) -> fastnbt::error::Result<(Vec<fastnbt::Value>, Vec<fastnbt::Value>)> {
+	match chunk_format {
+		ChunkFormat::V1_18 => fastnbt::from_bytes::<RawChunk1_18>(buf).map(|chunk| match (chunk.block_entities, chunk.level) {
+			(Some(block_entities), _) => (block_entities, Vec::new()),
+			(None, Some(level)) => (level.tile_entities, level.entities),
+			(None, None) => (Vec::new(), Vec::new()),
+		}),
+		ChunkFormat::V1_17 => fastnbt::from_bytes::<RawChunk>(buf).map(|chunk| (chunk.level.tile_entities, Vec::new())),
+		ChunkFormat::Legacy => fastnbt::from_bytes::<RawChunk>(buf).map(|chunk| (chunk.level.tile_entities, chunk.level.entities)),
+	}
+}
+
+fn raw_signs_of_chunk(buf: &[u8], chunk_format: ChunkFormat, dimension: Dimension) -> Vec<RawSign> {
+	let block_entities = match raw_block_entities(buf, chunk_format) {
+		Ok(block_entities) => block_entities,
+		Err(e) => {
+			warn!("failed to read raw nbt of chunk: {}", e);
+			return Vec::new();
+		}
+	};
+
+	let mut raw_signs = Vec::new();
+	for block_entity in &block_entities {
+		let is_sign = match compound_field(block_entity, "id") {
+			Some(fastnbt::Value::String(id)) => is_sign_id(id),
+			_ => false,
+		};
+		if !is_sign {
+			continue;
+		}
+		let coordinate = |name| match compound_field(block_entity, name) {
+			Some(fastnbt::Value::Int(value)) => *value,
+			_ => 0,
+		};
+		raw_signs.push(RawSign {
+			dimension,
+			x: coordinate("x"),
+			y: coordinate("y"),
+			z: coordinate("z"),
+			nbt: block_entity.clone(),
+		});
+	}
+	raw_signs
+}
+
+fn compound_field<'a>(value: &'a fastnbt::Value, name: &str) -> Option<&'a fastnbt::Value> {
+	match value {
+		fastnbt::Value::Compound(compound) => compound.get(name),
+		_ => None,
+	}
+}
+
+// extract signs and books from the block entities and entities of a chunk
+fn extract_from_chunk(block_entities: Vec<ChunkLevelTileEntities>, entities: Vec<Entity>, options: &ExtractOptions, result: &mut RegionResult) {
+	for block_entity in block_entities {
+		if options.list_block_entities {
+			*result.block_entity_ids.entry(block_entity.id.clone()).or_default() += 1;
+		}
+		extract_from_block_entity(block_entity, options, result);
+	}
+	for entity in entities {
+		extract_from_entity(entity, options, result);
+	}
+}
+
+// ids are matched case insensitively because their casing changed between versions and mods don't follow it
+// e.g. somewhere between 1.12.2 and 1.9.4 the sign id changed from "minecraft:sign" to "Sign"
+fn normalize_id(id: &str) -> String {
+	id.to_lowercase()
+}
+
+fn is_sign_id(id: &str) -> bool {
+	normalize_id(id).ends_with("sign")
+}
+
+// check if an item is a written or writable book
+fn is_book(item: &Item) -> bool {
+	let id = normalize_id(&item.id);
+	id.ends_with("book") && !id.ends_with("enchanted_book") && !id.ends_with(":book")
+}
+
+// where some mods keep the pages of a book instead of tag.pages
+const MODDED_BOOK_TAG_PATH: &str = "tag.book.pages";
+
+// get the book of an item if it is a book with pages
+// writable books that were never written in have no pages
+fn book_of(item: Item, book_tag_path: Option<&str>) -> Option<Book> {
+	// a custom path is for modded books so the item can have any id
+	if let Some(book) = book_tag_path.and_then(|path| book_at_path(&item, path)) {
+		return Some(book);
+	}
+	if !is_book(&item) {
+		return None;
+	}
+	match item.tag {
+		Some(ItemTag { pages: Some(pages), title, author, display, .. }) => Some(Book {
+			pages: Some(pages),
+			title,
+			author,
+			custom_name: display.name.map(|name| component_text(&name)),
+		}),
+		_ => book_at_path(&item, MODDED_BOOK_TAG_PATH),
+	}
+}
+
+// read the pages at a dotted path like tag.book.pages
+// the title and author are read from the same compound as the pages
+fn book_at_path(item: &Item, path: &str) -> Option<Book> {
+	let mut fields: Vec<&str> = path.strip_prefix("tag.")?.split('.').collect();
+	let pages_field = fields.pop()?;
+	let mut compound = &item.tag.as_ref()?.raw;
+	for field in fields {
+		compound = compound_field(compound, field)?;
+	}
+
+	let pages = match compound_field(compound, pages_field)? {
+		fastnbt::Value::List(pages) => pages.iter().filter_map(string_value).collect(),
+		_ => return None,
+	};
+	Some(Book {
+		pages: Some(pages),
+		title: compound_field(compound, "title").and_then(string_value),
+		author: compound_field(compound, "author").and_then(string_value),
+		custom_name: custom_name_of(item),
+	})
+}
+
+// read the fields of an item's tag that are used for books and containers
+impl From<fastnbt::Value> for ItemTag {
+	fn from(tag: fastnbt::Value) -> Self {
+		let strings = |value: Option<&fastnbt::Value>| match value {
+			Some(fastnbt::Value::List(values)) => Some(values.iter().filter_map(string_value).collect()),
+			_ => None,
+		};
+		let display = compound_field(&tag, "display");
+
+		// enchanted books keep their enchantments in StoredEnchantments and items before 1.13 in ench
+		let enchantments = ["Enchantments", "StoredEnchantments", "ench"].into_iter()
+			.filter_map(|field| compound_field(&tag, field))
+			.flat_map(|enchantments| match enchantments {
+				fastnbt::Value::List(enchantments) => enchantments.iter().collect(),
+				_ => Vec::new(),
+			})
+			.filter_map(|enchantment| {
+				let id = match compound_field(enchantment, "id")? {
+					fastnbt::Value::String(id) => id.clone(),
+					id => integer_value(id)?.to_string(),
+				};
+				let level = compound_field(enchantment, "lvl").and_then(integer_value).unwrap_or(1);
+				Some(Enchantment { id, level })
+			})
+			.collect();
+
+		ItemTag {
+			pages: strings(compound_field(&tag, "pages")),
+			title: compound_field(&tag, "title").and_then(string_value),
+			author: compound_field(&tag, "author").and_then(string_value),
+			display: ItemDisplay {
+				name: display.and_then(|display| compound_field(display, "Name")).and_then(string_value),
+				lore: strings(display.and_then(|display| compound_field(display, "Lore"))).unwrap_or_default(),
+			},
+			enchantments,
+			block_entity_items: match compound_field(&tag, "BlockEntityTag").and_then(|block_entity| compound_field(block_entity, "Items")) {
+				Some(fastnbt::Value::List(items)) => items.iter().filter_map(item_of).collect(),
+				_ => Vec::new(),
+			},
+			raw: tag,
+		}
+	}
+}
+
+impl From<ItemTag> for fastnbt::Value {
+	fn from(tag: ItemTag) -> Self {
+		tag.raw
+	}
+}
+
+// get the name an item was given in an anvil
+// the name is a json text component since 1.13 and raw text before that
+fn custom_name_of(item: &Item) -> Option<String> {
+	item.tag.as_ref()?.display.name.as_ref().map(|name| component_text(name))
+}
+
+// describe an item in a container with its name, lore and enchantments
+fn container_item_of(item: &Item) -> ContainerItem {
+	let tag = item.tag.as_ref();
+	ContainerItem {
+		id: item.id.clone(),
+		count: item.count,
+		slot: item.slot,
+		custom_name: custom_name_of(item),
+		// lore lines are json text components since 1.14 and raw text before that
+		lore: tag.map(|tag| tag.display.lore.iter().map(|line| component_text(line)).collect()).unwrap_or_default(),
+		enchantments: tag.map(|tag| tag.enchantments.clone()).unwrap_or_default(),
+		// shulker boxes keep their items when broken
+		contents: tag.map(|tag| tag.block_entity_items.iter().map(container_item_of).collect()).unwrap_or_default(),
+	}
+}
+
+// read an item out of raw nbt like the items in a BlockEntityTag
+fn item_of(value: &fastnbt::Value) -> Option<Item> {
+	Some(Item {
+		id: compound_field(value, "id").and_then(string_value)?,
+		slot: compound_field(value, "Slot").and_then(integer_value).map(|slot| slot as i8),
+		count: compound_field(value, "Count").and_then(integer_value).unwrap_or(1) as i8,
+		tag: compound_field(value, "tag").cloned().map(ItemTag::from),
+	})
+}
+
+// numbers are stored as the smallest type that fits in some versions so accept all of them
+fn integer_value(value: &fastnbt::Value) -> Option<i64> {
+	match value {
+		fastnbt::Value::Byte(value) => Some(*value as i64),
+		fastnbt::Value::Short(value) => Some(*value as i64),
+		fastnbt::Value::Int(value) => Some(*value as i64),
+		fastnbt::Value::Long(value) => Some(*value),
+		_ => None,
+	}
+}
+
+fn string_value(value: &fastnbt::Value) -> Option<String> {
+	match value {
+		fastnbt::Value::String(text) => Some(text.clone()),
+		_ => None,
+	}
+}
+
+fn extract_from_block_entity(mut block_entity: ChunkLevelTileEntities, options: &ExtractOptions, result: &mut RegionResult) {
+	let id = normalize_id(&block_entity.id);
+	// if block entity is a sign
+	if is_sign_id(&id) {
+		block_entity.dimension = options.dimension;
+		// the rest of the code reads the front of 1.20 signs from Text1-4
+		// some snapshots before 1.20 wrote front_text without messages and kept the lines in Text1-4
+		if let Some(front_text) = block_entity.front_text.as_ref().filter(|front_text| !front_text.messages.is_empty()) {
+			let mut messages = front_text.messages.iter().map(sign_message);
+			block_entity.text1 = messages.next();
+			block_entity.text2 = messages.next();
+			block_entity.text3 = messages.next();
+			block_entity.text4 = messages.next();
+		}
+		result.signs.push(block_entity);
+	}
+	// command blocks store the command they run
+	// before 1.11 the id was "Control"
+	else if id.ends_with("command_block") || id == "control" {
+		if let Some(command) = block_entity.command {
+			result.commands.push(CommandWithPos {
+				command,
+				source: "command_block".to_string(),
+				dimension: options.dimension,
+				x: block_entity.x,
+				y: block_entity.y,
+				z: block_entity.z,
+			});
+		}
+	}
+	// jigsaw and structure blocks are used by map makers to build and place structures
+	else if id.ends_with("jigsaw") || id.ends_with("structure_block") {
+		if options.structures {
+			result.structures.push(structure_of(&block_entity, &id, options.dimension));
+		}
+	}
+	// lecterns hold a single book
+	else if let Some(item) = block_entity.book {
+		if options.book_sources.contains(&BookSource::Lectern) {
+			if let Some(book) = book_of(item, options.book_tag_path.as_deref()) {
+				result.books.push(BookWithPos {
+					book,
+					dimension: options.dimension,
+					x: block_entity.x,
+					y: block_entity.y,
+					z: block_entity.z,
+					last_modified: None,
+					copies: Vec::new(),
+				});
+			}
+		}
+	}
+	// check if items are present
+	else if let Some(items) = container_items(&mut block_entity) {
+		if options.containers {
+			result.containers.push(ContainerWithPos {
+				block: id.clone(),
+				dimension: options.dimension,
+				x: block_entity.x,
+				y: block_entity.y,
+				z: block_entity.z,
+				items: items.iter().map(container_item_of).collect(),
+			});
+		}
+		if options.book_sources.contains(&BookSource::Container) {
+			for book in items.into_iter().filter_map(|item| book_of(item, options.book_tag_path.as_deref())) {
+				result.books.push(BookWithPos {
+					book,
+					dimension: options.dimension,
+					x: block_entity.x,
+					y: block_entity.y,
+					z: block_entity.z,
+					last_modified: None,
+					copies: Vec::new(),
+				});
+			}
+		}
+	}
+}
+
+// the items of a block entity under any of the keys containers use, none if it isn't a container
+fn container_items(block_entity: &mut ChunkLevelTileEntities) -> Option<Vec<Item>> {
+	if let Some(items) = block_entity.items.take() {
+		return Some(items);
+	}
+	if let Some(item) = block_entity.item.as_ref().or(block_entity.record_item.as_ref()) {
+		return Some(item_of(item).into_iter().collect());
+	}
+	let inventory = match &block_entity.inventory {
+		Some(fastnbt::Value::Compound(inventory)) => inventory.get("Items")?,
+		Some(inventory) => inventory,
+		None => return None,
+	};
+	match inventory {
+		fastnbt::Value::List(items) => Some(items.iter().filter_map(item_of).collect()),
+		_ => None,
+	}
+}
+
+// collect the string fields of a jigsaw or structure block
+fn structure_of(block_entity: &ChunkLevelTileEntities, id: &str, dimension: Dimension) -> StructureWithPos {
+	let fields = [
+		("name", &block_entity.name),
+		("target", &block_entity.target),
+		("pool", &block_entity.pool),
+		("final_state", &block_entity.final_state),
+		("joint", &block_entity.joint),
+		("author", &block_entity.author),
+		("metadata", &block_entity.metadata),
+		("mode", &block_entity.mode),
+	];
+	StructureWithPos {
+		block: if id.ends_with("jigsaw") { "jigsaw" } else { "structure_block" }.to_string(),
+		dimension,
+		x: block_entity.x,
+		y: block_entity.y,
+		z: block_entity.z,
+		fields: fields.into_iter()
+			.filter_map(|(name, value)| Some((name.to_string(), string_value(value.as_ref()?)?)))
+			.collect(),
+	}
+}
+
+fn extract_from_entity(entity: Entity, options: &ExtractOptions, result: &mut RegionResult) {
+	// books are either in item frames or dropped on the ground
+	// the item frame id was "ItemFrame" before 1.11
+	let id = normalize_id(&entity.id);
+	let source = if id.ends_with("item_frame") || id == "itemframe" {
+		BookSource::Frame
+	} else {
+		BookSource::Entity
+	};
+	if !options.book_sources.contains(&source) {
+		return;
+	}
+
+	// check if the entity holds a book
+	let book = match entity.item.and_then(|item| book_of(item, options.book_tag_path.as_deref())) {
+		Some(book) => book,
+		None => return,
+	};
+
+	// check if the entity has a valid position
+	// corrupt entities can have a short or empty Pos
+	if entity.pos.len() < 3 {
+		warn!("skipping book on {} with invalid position: {:?}", entity.id, entity.pos);
+		return;
+	}
+	// convert to BookWithPos and push to vector
+	// floor the position so negative coordinates land on the right block
+	result.books.push(BookWithPos {
+		book,
+		dimension: options.dimension,
+		x: entity.pos[0].floor() as i32,
+		y: entity.pos[1].floor() as i32,
+		z: entity.pos[2].floor() as i32,
+		last_modified: None,
+		copies: Vec::new(),
+	});
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashMap;
+	use flate2::write::ZlibEncoder;
+	use flate2::Compression;
+
+	fn compound(fields: Vec<(&str, fastnbt::Value)>) -> fastnbt::Value {
+		fastnbt::Value::Compound(fields.into_iter().map(|(name, value)| (name.to_string(), value)).collect::<HashMap<_, _>>())
+	}
+
+	fn string(text: &str) -> fastnbt::Value {
+		fastnbt::Value::String(text.to_string())
+	}
+
+	fn sign(x: i32, y: i32, z: i32, lines: [&str; 4]) -> fastnbt::Value {
+		compound(vec![
+			("id", string("minecraft:oak_sign")),
+			("x", fastnbt::Value::Int(x)),
+			("y", fastnbt::Value::Int(y)),
+			("z", fastnbt::Value::Int(z)),
+			("Text1", string(lines[0])),
+			("Text2", string(lines[1])),
+			("Text3", string(lines[2])),
+			("Text4", string(lines[3])),
+		])
+	}
+
+	// build a region file in memory with a single zlib compressed chunk at 0,0
+	fn region_with_chunk(chunk: &fastnbt::Value) -> Vec<u8> {
+		let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+		encoder.write_all(&fastnbt::to_bytes(chunk).unwrap()).unwrap();
+		let compressed = encoder.finish().unwrap();
+
+		// the chunk starts after the location and timestamp tables in sector 2
+		let sectors = (compressed.len() + 5).div_ceil(4096);
+		let mut region = vec![0; 8192];
+		region[0..4].copy_from_slice(&((2 << 8) | sectors as u32).to_be_bytes());
+		region.extend(((compressed.len() + 1) as u32).to_be_bytes());
+		region.push(2);
+		region.extend(compressed);
+		region.resize((2 + sectors) * 4096, 0);
+		region
+	}
+
+	#[test]
+	fn uppercase_region_extensions_are_read() {
+		assert_eq!(region_coordinates("r.-1.2.mca"), Some((-1, 2)));
+		assert_eq!(region_coordinates("r.-1.2.MCA"), Some((-1, 2)));
+		assert_eq!(region_coordinates("r.0.0.MCA.GZ"), Some((0, 0)));
+		assert_eq!(region_coordinates("R.0.0.mca"), None);
+		assert_eq!(region_coordinates("r.0.0.mcr"), Some((0, 0)));
+		// only anvil regions are decompressed as a whole
+		assert_eq!(region_coordinates("r.0.0.mcr.gz"), None);
+		assert_eq!(region_coordinates("r.0.0.mcx"), None);
+	}
+
+	fn extract(region: Vec<u8>, id: i32, name: &str) -> RegionResult {
+		let version = LevelDatDataVersion { id, name: name.to_string(), snapshot: false };
+		extract_signs_from_mca(std::io::Cursor::new(region), 0, 0, version, &options())
+	}
+
+	fn options() -> ExtractOptions {
+		ExtractOptions {
+			book_sources: vec![BookSource::Container, BookSource::Lectern, BookSource::Entity, BookSource::Frame],
+			book_tag_path: None,
+			dump_raw_signs: false,
+			dimension: Dimension::Overworld,
+			chunks: Vec::new(),
+			spawn_area: None,
+			structures: false,
+			containers: false,
+			timestamps: false,
+			modified_since: None,
+			ignore_version: false,
+			list_block_entities: false,
+			live: false,
+			entities: false,
+		}
+	}
+
+	#[test]
+	fn entity_books_are_read_from_both_entity_chunk_layouts() {
+		let frame = compound(vec![
+			("id", string("minecraft:item_frame")),
+			("Pos", fastnbt::Value::List(vec![fastnbt::Value::Double(1.5), fastnbt::Value::Double(64.5), fastnbt::Value::Double(2.5)])),
+			("Item", compound(vec![
+				("id", string("minecraft:written_book")),
+				("Count", fastnbt::Value::Byte(1)),
+				("tag", compound(vec![("title", string("framed")), ("pages", fastnbt::Value::List(vec![string(r#"{"text":"page"}"#)]))])),
+			])),
+		]);
+		let options = ExtractOptions { entities: true, ..options() };
+		let version = LevelDatDataVersion { id: 3465, name: "1.20.1".to_string(), snapshot: false };
+		for chunk in [
+			compound(vec![("Entities", fastnbt::Value::List(vec![frame.clone()]))]),
+			compound(vec![("Level", compound(vec![("Entities", fastnbt::Value::List(vec![frame.clone()]))]))]),
+		] {
+			let result = extract_signs_from_mca(std::io::Cursor::new(region_with_chunk(&chunk)), 0, 0, version.clone(), &options);
+			assert_eq!(result.books.len(), 1);
+			assert_eq!((result.books[0].x, result.books[0].y, result.books[0].z), (1, 64, 2));
+		}
+	}
+
+	#[test]
+	fn signs_are_extracted_from_a_1_18_region() {
+		let chunk = compound(vec![
+			("DataVersion", fastnbt::Value::Int(2975)),
+			("block_entities", fastnbt::Value::List(vec![sign(3, -20, 7, [r#"{"text":"hello"}"#, r#"{"text":""}"#, r#"{"text":""}"#, r#"{"text":""}"#])])),
+		]);
+		let result = extract(region_with_chunk(&chunk), 2975, "1.18.2");
+
+		assert_eq!(result.signs.len(), 1);
+		let sign = &result.signs[0];
+		assert_eq!((sign.x, sign.y, sign.z), (3, -20, 7));
+		let record = sign_record(sign, &LevelDatDataVersion { id: 2975, name: "1.18.2".to_string(), snapshot: false }, LineOptions::default());
+		assert_eq!(record.lines[0], "hello");
+		assert_eq!(record.provenance, Some(Provenance { chunk_format: "1.18".to_string(), text: "json".to_string(), dimension: Dimension::Overworld }));
+		assert_eq!(result.chunks.len(), 1);
+		assert_eq!(result.chunks[0].signs, 1);
+	}
+
+	#[test]
+	fn raw_json_keeps_lines_as_they_are_stored() {
+		let json_lines = [r#"{"text":"hello","color":"red"}"#, "", "", ""];
+		let new = LevelDatDataVersion { id: 2975, name: "1.18.2".to_string(), snapshot: false };
+		let raw_json = LineOptions { keep_formatting: false, raw_json: true };
+		assert_eq!(text_lines(json_lines.into_iter(), &new, raw_json)[0], json_lines[0]);
+
+		// old sign text isn't json so it is written like without --raw-json
+		let old = LevelDatDataVersion { id: 0, name: "old".to_string(), snapshot: false };
+		assert_eq!(text_lines(["§chello", "", "", ""].into_iter(), &old, raw_json)[0], "hello");
+	}
+
+	#[test]
+	fn broken_json_lines_are_kept_as_stored() {
+		let chunk = compound(vec![("block_entities", fastnbt::Value::List(vec![sign(0, 64, 0, [r#"{"text":"cut off"#, r#"{"text":"fine"}"#, "plain", ""])]))]);
+		let result = extract(region_with_chunk(&chunk), 2975, "1.18.2");
+		let record = sign_record(&result.signs[0], &LevelDatDataVersion { id: 2975, name: "1.18.2".to_string(), snapshot: false }, LineOptions::default());
+		assert_eq!(record.lines[0], r#"{"text":"cut off"#);
+		assert_eq!(record.raw_text, BTreeMap::from([("text1".to_string(), r#"{"text":"cut off"#.to_string())]));
+	}
+
+	#[test]
+	fn formatting_codes_are_removed_from_json_sign_text() {
+		let json_lines = [r#"{"text":"\u00a7cred \u00a7lsign"}"#, "", "", ""];
+		let new = LevelDatDataVersion { id: 2975, name: "1.18.2".to_string(), snapshot: false };
+		assert_eq!(text_lines(json_lines.into_iter(), &new, LineOptions::default())[0], "red sign");
+		let keep_formatting = LineOptions { keep_formatting: true, raw_json: false };
+		assert_eq!(text_lines(json_lines.into_iter(), &new, keep_formatting)[0], "§cred §lsign");
+	}
+
+	#[test]
+	fn signs_get_the_time_their_chunk_was_saved() {
+		let chunk = compound(vec![
+			("block_entities", fastnbt::Value::List(vec![sign(3, 64, 7, [r#"{"text":"hello"}"#, r#"{"text":""}"#, r#"{"text":""}"#, r#"{"text":""}"#])])),
+		]);
+		let mut region = region_with_chunk(&chunk);
+		region[4096..4100].copy_from_slice(&1_700_000_000u32.to_be_bytes());
+		let options = ExtractOptions { timestamps: true, ..options() };
+		let version = LevelDatDataVersion { id: 2975, name: "1.18.2".to_string(), snapshot: false };
+		let result = extract_signs_from_mca(std::io::Cursor::new(region.clone()), 0, 0, version.clone(), &options);
+		assert_eq!(result.signs[0].last_modified, Some(1_700_000_000));
+
+		// chunks saved before --modified-since are skipped even when the region file was written later
+		let options = ExtractOptions { modified_since: Some(1_700_000_001), ..options };
+		let result = extract_signs_from_mca(std::io::Cursor::new(region), 0, 0, version, &options);
+		assert!(result.signs.is_empty());
+	}
+
+	#[test]
+	fn old_chunks_in_a_1_18_world_are_read() {
+		let chunk = compound(vec![
+			("Level", compound(vec![
+				("TileEntities", fastnbt::Value::List(vec![sign(3, 64, 5, ["\"old chunk\"", "\"\"", "\"\"", "\"\""])])),
+			])),
+		]);
+		let result = extract(region_with_chunk(&chunk), 2975, "1.18.2");
+		assert_eq!(result.signs.len(), 1);
+		assert_eq!(result.failed_chunks, 0);
+	}
+
+	#[test]
+	fn entity_positions_are_floored() {
+		let entity = Entity {
+			id: "minecraft:item".to_string(),
+			pos: vec![-3.9, 64.0, -0.1],
+			item: Some(Item {
+				id: "minecraft:written_book".to_string(),
+				slot: None,
+				count: 1,
+				tag: Some(ItemTag::from(compound(vec![("title", string("Dropped")), ("pages", fastnbt::Value::List(vec![string(r#"{"text":"page"}"#)]))]))),
+			}),
+		};
+		let mut result = RegionResult::default();
+		extract_from_entity(entity, &options(), &mut result);
+		assert_eq!((result.books[0].x, result.books[0].y, result.books[0].z), (-4, 64, -1));
+	}
+
+	#[test]
+	fn container_items_have_lore_and_enchantments() {
+		let item = Item {
+			id: "minecraft:diamond_sword".to_string(),
+			slot: Some(3),
+			count: 1,
+			tag: Some(ItemTag::from(compound(vec![
+				("display", compound(vec![
+					("Name", string(r#"{"text":"Blade"}"#)),
+					("Lore", fastnbt::Value::List(vec![string(r#"{"text":"found in the ruins"}"#)])),
+				])),
+				("Enchantments", fastnbt::Value::List(vec![
+					compound(vec![("id", string("minecraft:sharpness")), ("lvl", fastnbt::Value::Short(5))]),
+				])),
+			]))),
+		};
+		let item = container_item_of(&item);
+		assert_eq!(item.custom_name.as_deref(), Some("Blade"));
+		assert_eq!(item.lore, vec!["found in the ruins"]);
+		assert_eq!((item.enchantments[0].id.as_str(), item.enchantments[0].level), ("minecraft:sharpness", 5));
+	}
+
+	#[test]
+	fn book_tag_path_is_read_from_the_raw_tag() {
+		let item = || Item {
+			id: "somemod:journal".to_string(),
+			slot: None,
+			count: 1,
+			tag: Some(ItemTag::from(compound(vec![
+				("journal", compound(vec![("title", string("Notes")), ("entries", fastnbt::Value::List(vec![string("first")]))])),
+			]))),
+		};
+		assert!(book_of(item(), None).is_none());
+		let book = book_of(item(), Some("tag.journal.entries")).unwrap();
+		assert_eq!((book.title.as_deref(), book.pages), (Some("Notes"), Some(vec!["first".to_string()])));
+	}
+
+	#[test]
+	fn copied_books_are_written_once() {
+		let book = |x: i32, page: &str| BookWithPos {
+			book: Book { pages: Some(vec![page.to_string()]), title: Some("Lore".to_string()), author: Some("Alex".to_string()), custom_name: None },
+			dimension: Dimension::Overworld,
+			x,
+			y: 64,
+			z: 0,
+			last_modified: None,
+			copies: Vec::new(),
+		};
+		let books = dedup_books(vec![book(1, r#"{"text":"once upon a time"}"#), book(2, r#"{"text":"§lonce upon a time"}"#), book(3, r#"{"text":"the end"}"#)]);
+		assert_eq!(books.len(), 2);
+		assert_eq!(books[0].copies, vec![Location { dimension: Dimension::Overworld, x: 2, y: 64, z: 0 }]);
+		assert!(books[1].copies.is_empty());
+	}
+
+	#[test]
+	fn at_reads_the_chunk_of_the_block() {
+		let opts = Opts::parse_from(["mc-sign-extractor", "--save", "world", "--at", "-17", "64", "5"]);
+		assert_eq!(opts.extract_options().chunks, vec![(-2, 0)]);
+		assert!(opts.position_wanted(-17, 64, 5));
+		assert!(!opts.position_wanted(-17, 65, 5));
+	}
+
+	#[test]
+	fn limit_only_counts_signs_between_min_y_and_max_y() {
+		let opts = Opts::parse_from(["mc-sign-extractor", "--save", "world", "--limit", "1", "--min-y", "70"]);
+		let version = LevelDatDataVersion { id: 2975, name: "1.18.2".to_string(), snapshot: false };
+		let region = |y: i32| {
+			let chunk = compound(vec![("block_entities", fastnbt::Value::List(vec![sign(0, y, 0, ["a", "", "", ""]), sign(1, y, 0, ["b", "", "", ""])]))]);
+			let mut result = extract(region_with_chunk(&chunk), 2975, "1.18.2");
+			keep_wanted(&mut result, &opts, &version);
+			result
+		};
+		reset_limit();
+		let low = region(10);
+		count_found(&low, opts.limit);
+		assert!(low.signs.is_empty());
+		assert!(!stop_scanning());
+		let high = region(80);
+		count_found(&high, opts.limit);
+		assert_eq!(high.signs.len(), 2);
+		assert!(stop_scanning());
+		reset_limit();
+	}
+
+	#[test]
+	fn limit_only_counts_signs_with_the_included_text() {
+		let opts = Opts::parse_from(["mc-sign-extractor", "--save", "world", "--limit", "1", "--include-text", "diamond"]);
+		let version = LevelDatDataVersion { id: 2975, name: "1.18.2".to_string(), snapshot: false };
+		let wanted = |text: &str| {
+			let chunk = compound(vec![("block_entities", fastnbt::Value::List(vec![sign(0, 64, 0, [text, r#""""#, r#""""#, r#""""#])]))]);
+			let mut result = extract(region_with_chunk(&chunk), 2975, "1.18.2");
+			keep_wanted(&mut result, &opts, &version);
+			result.signs.len()
+		};
+		assert_eq!(wanted(r#"{"text":"dirt"}"#), 0);
+		assert_eq!(wanted(r#"{"text":"diamonds here"}"#), 1);
+	}
+
+	#[test]
+	fn sign_lines_are_numbered_unless_legacy_format_is_given() {
+		let chunk = compound(vec![("block_entities", fastnbt::Value::List(vec![sign(0, 64, 0, [r#"{"text":"top"}"#, r#""""#, r#""""#, r#"{"text":"bottom"}"#])]))]);
+		let version = LevelDatDataVersion { id: 2975, name: "1.18.2".to_string(), snapshot: false };
+		let text = |args: &[&str]| {
+			let opts = Opts::parse_from(["mc-sign-extractor", "--save", "world"].iter().chain(args));
+			let mut text = Vec::new();
+			write_signs_text(&mut text, extract(region_with_chunk(&chunk), 2975, "1.18.2").signs, &version, &opts);
+			String::from_utf8(text).unwrap()
+		};
+		assert!(text(&[]).contains("line1: top\nline2: \nline3: \nline4: bottom\n"));
+		assert!(text(&["--legacy-format"]).contains("text: top\ntext: \ntext: \ntext: bottom\n"));
+	}
+
+	#[test]
+	fn output_files_are_named_after_the_save_with_more_than_one() {
+		let one = Opts::parse_from(["mc-sign-extractor", "--save", "world"]);
+		assert_eq!(one.output_path(Path::new("out/manifest.csv"), "world"), PathBuf::from("out/manifest.csv"));
+		let more = Opts::parse_from(["mc-sign-extractor", "--save", "world", "--save", "creative"]);
+		assert_eq!(more.output_path(Path::new("out/manifest.csv"), "creative"), PathBuf::from("out/manifest-creative.csv"));
+		assert_eq!(more.output_path(Path::new("errors"), "world"), PathBuf::from("errors-world"));
+	}
+
+	#[test]
+	fn book_stats_count_different_authors_and_titles() {
+		let book = |title: Option<&str>, author: Option<&str>, pages: usize| BookWithPos {
+			book: Book { pages: Some(vec![String::new(); pages]), title: title.map(str::to_string), author: author.map(str::to_string), custom_name: None },
+			dimension: Dimension::Overworld,
+			x: 0,
+			y: 64,
+			z: 0,
+			last_modified: None,
+			copies: Vec::new(),
+		};
+		let books = [book(Some("Lore"), Some("Alex"), 2), book(Some("Lore"), Some("Steve"), 3), book(Some("Diary"), Some("Alex"), 1), book(None, None, 4)];
+		assert_eq!(book_stats(&books), BookStats { books: 4, authors: 2, titles: 2, pages: 10 });
+	}
+
+	#[test]
+	fn books_are_written_as_markdown() {
+		let book = BookWithPos {
+			book: Book { pages: Some(vec![r#"{"text":"§lchapter 1\n- the *start*"}"#.to_string(), r#"{"text":"the end"}"#.to_string()]), title: Some("Lore".to_string()), author: None, custom_name: None },
+			dimension: Dimension::Overworld,
+			x: 1,
+			y: 64,
+			z: 2,
+			last_modified: None,
+			copies: Vec::new(),
+		};
+		let mut markdown = Vec::new();
+		write_books_markdown(&mut markdown, &[book]);
+		assert_eq!(String::from_utf8(markdown).unwrap(), "## Lore\n\nFound at 1,64,2\n\nchapter 1  \n\\- the \\*start\\*\n\n---\n\nthe end\n\n");
+	}
+
+	#[test]
+	fn flattened_books_have_no_page_headers() {
+		let book = Book { pages: Some(vec!["first".to_string(), "second".to_string()]), title: Some("Lore".to_string()), author: None, custom_name: None };
+		let mut text = Vec::new();
+		write_book_text(&mut text, book, false, true);
+		assert_eq!(String::from_utf8(text).unwrap(), "title: Lore\nauthor: unknown\npages: 2\n---------- text ----------\nfirst\nsecond\n\n");
+	}
+
+	#[test]
+	fn signs_are_written_as_setblock_commands() {
+		let chunk = compound(vec![("block_entities", fastnbt::Value::List(vec![sign(1, 64, -2, [r#"{"text":"it's"}"#, r#"{"text":"here"}"#, "", r#""""#])]))]);
+		let mut result = extract(region_with_chunk(&chunk), 2975, "1.18.2");
+		result.signs[0].rotation = Some(8);
+		let mut commands = Vec::new();
+		write_signs_mcfunction(&mut commands, &result.signs, &LevelDatDataVersion { id: 2975, name: "1.18.2".to_string(), snapshot: false });
+		assert_eq!(
+			String::from_utf8(commands).unwrap(),
+			"execute in minecraft:overworld run setblock 1 64 -2 minecraft:oak_sign[rotation=8]{front_text:{messages:['{\"text\":\"it\\'s\"}','{\"text\":\"here\"}','\"\"','\"\"']}}\n",
+		);
+	}
+
+	#[test]
+	fn shulker_box_contents_are_listed() {
+		let item = Item {
+			id: "minecraft:shulker_box".to_string(),
+			slot: Some(0),
+			count: 1,
+			tag: Some(ItemTag::from(compound(vec![
+				("BlockEntityTag", compound(vec![
+					("Items", fastnbt::Value::List(vec![
+						compound(vec![("id", string("minecraft:diamond")), ("Slot", fastnbt::Value::Byte(4)), ("Count", fastnbt::Value::Byte(64))]),
+					])),
+				])),
+			]))),
+		};
+		let item = container_item_of(&item);
+		assert_eq!(item.contents.len(), 1);
+		assert_eq!((item.contents[0].id.as_str(), item.contents[0].count, item.contents[0].slot), ("minecraft:diamond", 64, Some(4)));
+	}
+
+	#[test]
+	fn books_are_found_under_every_container_key() {
+		let book = |title: &str| compound(vec![
+			("id", string("minecraft:written_book")),
+			("Slot", fastnbt::Value::Byte(0)),
+			("Count", fastnbt::Value::Byte(1)),
+			("tag", compound(vec![("title", string(title)), ("author", string("Steve")), ("pages", fastnbt::Value::List(vec![string(r#"{"text":"page"}"#)]))])),
+		]);
+		let container = |id: &str, key: &str, items: fastnbt::Value| compound(vec![
+			("id", string(id)),
+			("x", fastnbt::Value::Int(0)),
+			("y", fastnbt::Value::Int(64)),
+			("z", fastnbt::Value::Int(0)),
+			(key, items),
+		]);
+		let chunk = compound(vec![("block_entities", fastnbt::Value::List(vec![
+			container("minecraft:hopper", "Items", fastnbt::Value::List(vec![book("hopper")])),
+			container("minecraft:dispenser", "Items", fastnbt::Value::List(vec![book("dispenser")])),
+			container("minecraft:decorated_pot", "item", book("pot")),
+			container("somemod:crate", "Inventory", fastnbt::Value::List(vec![book("crate")])),
+			container("othermod:cabinet", "Inventory", compound(vec![("Items", fastnbt::Value::List(vec![book("cabinet")]))])),
+		]))]);
+		let result = extract(region_with_chunk(&chunk), 2975, "1.18.2");
+		let mut titles: Vec<String> = result.books.into_iter().filter_map(|book| book.book.title).collect();
+		titles.sort();
+		assert_eq!(titles, vec!["cabinet", "crate", "dispenser", "hopper", "pot"]);
+	}
+
+	#[test]
+	fn jukebox_discs_are_written_with_the_containers() {
+		let chunk = compound(vec![("block_entities", fastnbt::Value::List(vec![compound(vec![
+			("id", string("minecraft:jukebox")),
+			("x", fastnbt::Value::Int(3)),
+			("y", fastnbt::Value::Int(64)),
+			("z", fastnbt::Value::Int(-2)),
+			("RecordItem", compound(vec![("id", string("minecraft:music_disc_cat")), ("Count", fastnbt::Value::Byte(1))])),
+		])]))]);
+		let version = LevelDatDataVersion { id: 2975, name: "1.18.2".to_string(), snapshot: false };
+		let options = ExtractOptions { containers: true, ..options() };
+		let result = extract_signs_from_mca(std::io::Cursor::new(region_with_chunk(&chunk)), 0, 0, version, &options);
+		assert_eq!(result.containers.len(), 1);
+		assert_eq!((result.containers[0].block.as_str(), result.containers[0].x, result.containers[0].z), ("minecraft:jukebox", 3, -2));
+		assert_eq!(result.containers[0].items[0].id, "minecraft:music_disc_cat");
+	}
+
+	// a 1.20 sign with the text on both sides
+	fn sign_1_20(x: i32, y: i32, z: i32, front: [fastnbt::Value; 4], back: [fastnbt::Value; 4]) -> fastnbt::Value {
+		compound(vec![
+			("id", string("minecraft:oak_hanging_sign")),
+			("x", fastnbt::Value::Int(x)),
+			("y", fastnbt::Value::Int(y)),
+			("z", fastnbt::Value::Int(z)),
+			("front_text", compound(vec![("messages", fastnbt::Value::List(front.to_vec())), ("color", string("black"))])),
+			("back_text", compound(vec![("messages", fastnbt::Value::List(back.to_vec())), ("color", string("black"))])),
+			("is_waxed", fastnbt::Value::Byte(0)),
+		])
+	}
+
+	#[test]
+	fn signs_are_extracted_from_every_version() {
+		let empty = || string(r#""""#);
+		let versions = [
+			// 1.8 has raw text and no data version
+			(19133, "old", compound(vec![("Level", compound(vec![
+				("TileEntities", fastnbt::Value::List(vec![sign(1, 64, 1, ["1.8", "", "", ""])])),
+				("Entities", fastnbt::Value::List(Vec::new())),
+			]))])),
+			// 1.13 has json text and the flattened ids
+			(1519, "1.13", compound(vec![("Level", compound(vec![
+				("TileEntities", fastnbt::Value::List(vec![sign(1, 64, 1, [r#"{"text":"1.13"}"#, "\"\"", "\"\"", "\"\""])])),
+				("Entities", fastnbt::Value::List(Vec::new())),
+			]))])),
+			// 1.17 keeps the entities in their own folder
+			(2724, "1.17", compound(vec![("Level", compound(vec![
+				("TileEntities", fastnbt::Value::List(vec![sign(1, -10, 1, [r#"{"text":"1.17"}"#, "\"\"", "\"\"", "\"\""])])),
+			]))])),
+			// 1.18 moved the block entities to the root of the chunk
+			(2975, "1.18.2", compound(vec![
+				("block_entities", fastnbt::Value::List(vec![sign(1, -60, 1, [r#"{"text":"1.18"}"#, "\"\"", "\"\"", "\"\""])])),
+			])),
+			// 1.20 signs have a front and a back
+			(3463, "1.20", compound(vec![
+				("block_entities", fastnbt::Value::List(vec![sign_1_20(1, 64, 1,
+					[string(r#"{"text":"1.20"}"#), empty(), empty(), empty()],
+					[string(r#"{"text":"back"}"#), empty(), empty(), empty()],
+				)])),
+			])),
+			// 1.21.5 stores the text components as nbt
+			(4325, "1.21.5", compound(vec![
+				("block_entities", fastnbt::Value::List(vec![sign_1_20(1, 64, 1,
+					[compound(vec![("text", string("1.21"))]), compound(vec![("", string(""))]), compound(vec![("", string(""))]), compound(vec![("", string(""))])],
+					[string(""), string(""), string(""), string("")],
+				)])),
+			])),
+		];
+		for (id, name, chunk) in versions {
+			let result = extract(region_with_chunk(&chunk), id, name);
+			assert_eq!(result.signs.len(), 1, "{}", name);
+			let version = LevelDatDataVersion { id, name: name.to_string(), snapshot: false };
+			let record = sign_record(&result.signs[0], &version, LineOptions::default());
+			let expected = if name == "old" { "1.8" } else { &name[..4] };
+			assert_eq!(record.lines[0], expected, "{}", name);
+			assert_eq!(record.lines.len(), 4, "{}", name);
+			assert_eq!(record.back_lines, if name == "1.20" { vec!["back", "", "", ""] } else { Vec::<&str>::new() }, "{}", name);
+		}
+	}
+
+	#[test]
+	fn sign_colors_and_wax_are_read() {
+		let mut dyed = sign_1_20(0, 64, 0, [string("\"a\""), string("\"\""), string("\"\""), string("\"\"")], [string("\"\""), string("\"\""), string("\"\""), string("\"\"")]);
+		if let fastnbt::Value::Compound(fields) = &mut dyed {
+			fields.insert("is_waxed".to_string(), fastnbt::Value::Byte(1));
+			fields.insert("back_text".to_string(), compound(vec![("messages", fastnbt::Value::List(Vec::new())), ("color", string("lime"))]));
+		}
+		let old = compound(vec![
+			("id", string("minecraft:sign")),
+			("x", fastnbt::Value::Int(1)),
+			("y", fastnbt::Value::Int(64)),
+			("z", fastnbt::Value::Int(0)),
+			("Text1", string("\"b\"")),
+			("Color", string("red")),
+		]);
+		let chunk = compound(vec![("block_entities", fastnbt::Value::List(vec![dyed, old]))]);
+		let version = LevelDatDataVersion { id: 3463, name: "1.20".to_string(), snapshot: false };
+		let result = extract(region_with_chunk(&chunk), 3463, "1.20");
+		let records: Vec<SignRecord> = result.signs.iter().map(|sign| sign_record(sign, &version, LineOptions::default())).collect();
+		assert_eq!((records[0].color.as_deref(), records[0].back_color.as_deref(), records[0].waxed), (Some("black"), Some("lime"), true));
+		assert_eq!((records[1].color.as_deref(), records[1].back_color.as_deref(), records[1].waxed), (Some("red"), None, false));
+	}
+
+	#[test]
+	fn forge_chunks_are_read() {
+		// forge adds compounds next to the vanilla ones and mods can put anything in their block entities
+		let chunk = compound(vec![
+			("DataVersion", fastnbt::Value::Int(2975)),
+			("ForgeCaps", compound(vec![("somemod:data", compound(vec![("charge", fastnbt::Value::Int(5))]))])),
+			("block_entities", fastnbt::Value::List(vec![
+				sign(3, 64, 7, [r#"{"text":"forge"}"#, r#"{"text":""}"#, r#"{"text":""}"#, r#"{"text":""}"#]),
+				compound(vec![
+					("id", string("somemod:crate")),
+					("x", fastnbt::Value::Int(4)),
+					("y", fastnbt::Value::Int(64)),
+					("z", fastnbt::Value::Int(7)),
+					("Items", compound(vec![("Size", fastnbt::Value::Int(27))])),
+					("ForgeData", compound(vec![("owner", string("someone"))])),
+				]),
+			])),
+		]);
+		let result = extract(region_with_chunk(&chunk), 2975, "1.18.2");
+		assert_eq!(result.failed_chunks, 0);
+		assert_eq!(result.signs.len(), 1);
+		assert_eq!(result.signs[0].text1.as_deref(), Some(r#"{"text":"forge"}"#));
+	}
+
+	#[test]
+	fn entity_books_are_kept_when_a_block_entity_can_not_be_read() {
+		let chunk = compound(vec![
+			("Level", compound(vec![
+				("TileEntities", fastnbt::Value::List(vec![
+					compound(vec![
+						("id", string("somemod:crate")),
+						("x", fastnbt::Value::Int(4)),
+						("y", fastnbt::Value::Int(64)),
+						("z", fastnbt::Value::Int(7)),
+						("Items", compound(vec![("Size", fastnbt::Value::Int(27))])),
+					]),
+				])),
+				("Entities", fastnbt::Value::List(vec![compound(vec![
+					("id", string("minecraft:item_frame")),
+					("Pos", fastnbt::Value::List(vec![fastnbt::Value::Double(1.5), fastnbt::Value::Double(64.5), fastnbt::Value::Double(2.5)])),
+					("Item", compound(vec![
+						("id", string("minecraft:written_book")),
+						("Count", fastnbt::Value::Byte(1)),
+						("tag", compound(vec![("title", string("framed")), ("pages", fastnbt::Value::List(vec![string(r#"{"text":"page"}"#)]))])),
+					])),
+				])])),
+			])),
+		]);
+		let result = extract(region_with_chunk(&chunk), 1343, "1.12.2");
+		assert_eq!(result.failed_chunks, 0);
+		assert_eq!(result.books.len(), 1);
+	}
+
+	#[test]
+	fn signs_are_extracted_from_an_old_region() {
+		let chunk = compound(vec![
+			("Level", compound(vec![
+				("TileEntities", fastnbt::Value::List(vec![sign(-1, 64, 2, ["raw", "text", "", ""])])),
+				("Entities", fastnbt::Value::List(vec![])),
+			])),
+		]);
+		let result = extract(region_with_chunk(&chunk), 19133, "old");
+
+		assert_eq!(result.signs.len(), 1);
+		assert_eq!(result.signs[0].text1.as_deref(), Some("raw"));
+	}
+
+	#[test]
+	fn signs_are_extracted_from_a_mcregion_chunk() {
+		// a standing sign with rotation 8 at local x 15, z 2 and y 64 of the blocks of the whole chunk
+		let index = (15 * 16 + 2) * 128 + 64;
+		let mut blocks = vec![0; 32768];
+		blocks[index] = 63;
+		let mut data = vec![0; 16384];
+		data[index / 2] = 8;
+		let chunk = compound(vec![
+			("Level", compound(vec![
+				("TileEntities", fastnbt::Value::List(vec![sign(-1, 64, 2, ["beta", "", "", ""])])),
+				("Blocks", fastnbt::Value::ByteArray(fastnbt::ByteArray::new(blocks))),
+				("Data", fastnbt::Value::ByteArray(fastnbt::ByteArray::new(data))),
+			])),
+		]);
+		let result = extract(region_with_chunk(&chunk), 19132, "old");
+
+		assert_eq!(result.signs.len(), 1);
+		assert_eq!(result.signs[0].text1.as_deref(), Some("beta"));
+		assert_eq!(result.signs[0].rotation, Some(8));
+	}
+
+	#[test]
+	fn ignore_version_reads_chunks_of_another_format() {
+		// a 1.18 chunk in a world that level.dat says is from 1.12
+		let chunk = compound(vec![
+			("block_entities", fastnbt::Value::List(vec![sign(1, 70, 1, [r#"{"text":"moved"}"#, r#"{"text":""}"#, r#"{"text":""}"#, r#"{"text":""}"#])])),
+		]);
+		let result = extract(region_with_chunk(&chunk), 1343, "1.12.2");
+		assert_eq!(result.failed_chunks, 1);
+
+		let version = LevelDatDataVersion { id: 1343, name: "1.12.2".to_string(), snapshot: false };
+		let options = ExtractOptions { ignore_version: true, ..options() };
+		let result = extract_signs_from_mca(std::io::Cursor::new(region_with_chunk(&chunk)), 0, 0, version, &options);
+		assert_eq!(result.failed_chunks, 0);
+		assert_eq!(result.signs.len(), 1);
+
+		// snapshots are read the same way without the option
+		let snapshot = LevelDatDataVersion { id: 1343, name: "17w43a".to_string(), snapshot: true };
+		let options = ExtractOptions { ignore_version: false, ..options };
+		let result = extract_signs_from_mca(std::io::Cursor::new(region_with_chunk(&chunk)), 0, 0, snapshot, &options);
+		assert_eq!(result.signs.len(), 1);
+	}
+
+	#[test]
+	fn signs_with_an_empty_front_text_keep_their_old_lines() {
+		let mut sign = sign(0, 64, 0, [r#"{"text":"snapshot"}"#, "\"\"", "\"\"", "\"\""]);
+		if let fastnbt::Value::Compound(fields) = &mut sign {
+			fields.insert("front_text".to_string(), compound(vec![("messages", fastnbt::Value::List(Vec::new()))]));
+		}
+		let chunk = compound(vec![("block_entities", fastnbt::Value::List(vec![sign]))]);
+		let result = extract(region_with_chunk(&chunk), 3442, "23w12a");
+		assert_eq!(result.signs[0].text1.as_deref(), Some(r#"{"text":"snapshot"}"#));
+	}
+
+	#[test]
+	fn signs_are_extracted_from_a_cubic_chunks_region() {
+		let cube = compound(vec![
+			("Level", compound(vec![
+				("TileEntities", fastnbt::Value::List(vec![sign(5, 300, 5, ["high", "up", "", ""])])),
+			])),
+		]);
+		let mut encoder = flate2::write::GzEncoder::new(Vec::new(), Compression::default());
+		encoder.write_all(&fastnbt::to_bytes(&cube).unwrap()).unwrap();
+		let compressed = encoder.finish().unwrap();
+
+		// the header has an entry for each of the 16x16x16 cubes and the sectors are 512 bytes
+		let mut region = vec![0; 4096 * 4];
+		let sectors = (compressed.len() + 4).div_ceil(512);
+		region[0..4].copy_from_slice(&((((4096 * 4 / 512) << 8) | sectors) as u32).to_be_bytes());
+		region.extend((compressed.len() as u32).to_be_bytes());
+		region.extend(compressed);
+
+		let result = extract_signs_from_3dr(&region[..], "0.18.0.3dr", &options());
+		assert_eq!(result.signs.len(), 1);
+		assert_eq!(result.signs[0].y, 300);
+	}
+
+	#[test]
+	fn raw_signs_skip_the_block_states_of_the_chunk() {
+		let chunk = compound(vec![
+			("sections", fastnbt::Value::List(vec![compound(vec![
+				("block_states", compound(vec![("data", fastnbt::Value::LongArray(fastnbt::LongArray::new(vec![0; 4096])))])),
+			])])),
+			("block_entities", fastnbt::Value::List(vec![sign(1, 2, 3, ["", "", "", ""])])),
+		]);
+		let raw_signs = raw_signs_of_chunk(&fastnbt::to_bytes(&chunk).unwrap(), ChunkFormat::V1_18, Dimension::Overworld);
+		assert_eq!(raw_signs.len(), 1);
+	}
+
+	#[test]
+	fn long_and_int_array_fields_are_read_from_a_chunk() {
+		// heightmaps are long arrays that none of the types declare, they have to be skipped without failing the chunk
+		let data = vec![0x1234_5678_9abc_def0, -1, 0];
+		let chunk = compound(vec![
+			("Heightmaps", compound(vec![("MOTION_BLOCKING", fastnbt::Value::LongArray(fastnbt::LongArray::new(vec![7; 37])))])),
+			("sections", fastnbt::Value::List(vec![compound(vec![
+				("Y", fastnbt::Value::Byte(0)),
+				("block_states", compound(vec![
+					("palette", fastnbt::Value::List(vec![compound(vec![("Name", string("minecraft:air"))]), compound(vec![("Name", string("minecraft:oak_sign"))])])),
+					("data", fastnbt::Value::LongArray(fastnbt::LongArray::new(data.clone()))),
+				])),
+			])])),
+			("block_entities", fastnbt::Value::List(vec![sign(1, 2, 3, ["", "", "", ""])])),
+		]);
+		let buf = fastnbt::to_bytes(&chunk).unwrap();
+		let sections: ChunkSections1_18 = fastnbt::from_bytes(&buf).unwrap();
+		let block_states = sections.sections[0].block_states.as_ref().unwrap();
+		assert_eq!(block_states.data.as_deref(), Some(&data[..]));
+		let (block_entities, _) = read_chunk(&buf, ChunkFormat::V1_18).unwrap();
+		assert_eq!(block_entities.len(), 1);
+
+		let poi = compound(vec![("Sections", compound(vec![("4", compound(vec![("Records", fastnbt::Value::List(vec![compound(vec![
+			("type", string("minecraft:librarian")),
+			("pos", fastnbt::Value::IntArray(fastnbt::IntArray::new(vec![-12, 70, 300]))),
+		])]))]))]))]);
+		let poi: PoiChunk = fastnbt::from_bytes(&fastnbt::to_bytes(&poi).unwrap()).unwrap();
+		assert_eq!(&poi.sections["4"].records[0].pos[..], &[-12, 70, 300]);
+	}
+
+	#[test]
+	fn chunks_with_the_wrong_compression_type_are_still_read() {
+		let mut encoder = flate2::write::GzEncoder::new(Vec::new(), Compression::default());
+		encoder.write_all(b"chunk").unwrap();
+		let gzip = encoder.finish().unwrap();
+		assert_eq!(decompress_chunk(&gzip, false).unwrap(), b"chunk");
+
+		let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+		encoder.write_all(b"chunk").unwrap();
+		let zlib = encoder.finish().unwrap();
+		assert_eq!(decompress_chunk(&zlib, true).unwrap(), b"chunk");
+		assert!(decompress_chunk(b"not compressed", false).is_err());
+	}
+
+	#[test]
+	fn modified_since_too_far_back_is_an_error() {
+		assert!(parse_modified_since("2d").is_ok());
+		assert_eq!(parse_modified_since("9999999999999999999w"), Err("duration too large: 9999999999999999999w".to_string()));
+	}
+
+	#[test]
+	fn chunk_positions_are_mapped_to_regions() {
+		assert_eq!(parse_chunk_position("-3,5"), Ok((-3, 5)));
+		assert!(parse_chunk_position("3").is_err());
+
+		let options = ExtractOptions { chunks: vec![(-3, 5), (40, 0)], ..options() };
+		assert!(options.region_wanted(-1, 0));
+		assert!(options.region_wanted(1, 0));
+		assert!(!options.region_wanted(0, 0));
+		assert!(options.chunk_wanted(-3, 5));
+		assert!(!options.chunk_wanted(3, 5));
+	}
+
+	#[test]
+	fn broken_level_dat_falls_back_to_level_dat_old() {
+		let level_dat = compound(vec![("Data", compound(vec![
+			("version", fastnbt::Value::Int(19133)),
+			("RandomSeed", fastnbt::Value::Long(42)),
+		]))]);
+		let mut encoder = flate2::write::GzEncoder::new(Vec::new(), Compression::default());
+		encoder.write_all(&fastnbt::to_bytes(&level_dat).unwrap()).unwrap();
+		let level_dat_old = encoder.finish().unwrap();
+
+		let level_dat = parse_level_dat(vec![("level.dat", Ok(vec![0x1f, 0x8b, 0])), ("level.dat_old", Ok(level_dat_old))]).unwrap();
+		assert_eq!(level_dat.data.seed(), Some(42));
+		assert!(parse_level_dat(Vec::new()).is_none());
+	}
+
+	#[test]
+	fn spawn_area_covers_the_regions_around_spawn() {
+		let options = ExtractOptions { spawn_area: Some((30, -2, 4)), ..options() };
+		assert!(options.region_wanted(0, -1));
+		assert!(options.region_wanted(1, 0));
+		assert!(!options.region_wanted(2, 0));
+		assert!(options.chunk_wanted(34, 2));
+		assert!(!options.chunk_wanted(35, 2));
+	}
+
+	#[test]
+	fn corrupt_chunks_are_counted() {
+		let mut region = region_with_chunk(&compound(vec![("block_entities", fastnbt::Value::List(Vec::new()))]));
+		// overwrite the compressed data after the length and compression type
+		region[8192 + 5..8192 + 15].fill(0xff);
+		let result = extract(region, 2975, "1.18.2");
+		assert_eq!((result.chunks_with_data, result.failed_chunks), (1, 1));
+	}
+
+	#[test]
+	fn chunks_cut_off_by_a_save_are_failed() {
+		let mut region = region_with_chunk(&compound(vec![("block_entities", fastnbt::Value::List(Vec::new()))]));
+		region.truncate(8192 + 2);
+		let result = extract(region, 2975, "1.18.2");
+		assert_eq!(result.failed_chunk_positions, vec![(0, 0)]);
+	}
+
+	#[test]
+	fn chunk_errors_are_reported_with_their_region() {
+		let mut region = region_with_chunk(&compound(vec![("block_entities", fastnbt::Value::List(Vec::new()))]));
+		region[8192 + 4] = 7;
+		let result = with_region_info(extract(region, 2975, "1.18.2"), Path::new("region/r.0.0.mca"), 0, Dimension::Overworld, None);
+		assert_eq!(result.errors.len(), 1);
+		let error = &result.errors[0];
+		assert_eq!((error.region.as_str(), error.chunk_x, error.chunk_z, error.kind), ("r.0.0.mca", Some(0), Some(0), ReadErrorKind::UnsupportedCompression));
+	}
+
+	#[test]
+	fn chunks_with_invalid_lengths_are_skipped() {
+		for length in [0, 4093, u32::MAX] {
+			let mut region = region_with_chunk(&compound(vec![("block_entities", fastnbt::Value::List(Vec::new()))]));
+			region[8192..8196].copy_from_slice(&length.to_be_bytes());
+			let result = extract(region, 2975, "1.18.2");
+			assert_eq!(result.failed_chunks, 1);
+		}
+	}
+
+	#[test]
+	fn positions_sort_by_distance_from_the_origin() {
+		let mut positions = vec![(100, 64, 0), (-3, 10, 4), (0, 70, -50)];
+		positions.sort_by(|a, b| SortBy::Dist.compare(*a, *b));
+		assert_eq!(positions, vec![(-3, 10, 4), (0, 70, -50), (100, 64, 0)]);
+		positions.sort_by(|a, b| SortBy::Y.compare(*a, *b));
+		assert_eq!(positions, vec![(-3, 10, 4), (100, 64, 0), (0, 70, -50)]);
+	}
+
+	#[test]
+	fn empty_regions_have_no_signs() {
+		let result = extract(Vec::new(), 2975, "1.18.2");
+		assert!(result.signs.is_empty());
+		assert!(result.chunks.is_empty());
+	}
+
+	#[test]
+	fn sign_ids_match_regardless_of_case() {
+		assert!(is_sign_id("minecraft:oak_sign"));
+		assert!(is_sign_id("Sign"));
+		assert!(is_sign_id("SomeMod:Painted_SIGN"));
+		assert!(!is_sign_id("minecraft:chest"));
+	}
+
+	#[test]
+	fn sign_kinds_come_from_the_id_and_the_orientation() {
+		let block_entity = |id: &str, rotation: Option<u8>, facing: Option<&str>| {
+			let mut sign: ChunkLevelTileEntities = fastnbt::from_bytes(&fastnbt::to_bytes(&compound(vec![
+				("id", string(id)),
+				("x", fastnbt::Value::Int(0)),
+				("y", fastnbt::Value::Int(0)),
+				("z", fastnbt::Value::Int(0)),
+			])).unwrap()).unwrap();
+			sign.rotation = rotation;
+			sign.facing = facing.map(str::to_string);
+			sign_kind(&sign)
+		};
+		assert_eq!(block_entity("minecraft:sign", Some(4), None), Some(SignKind::Standing));
+		assert_eq!(block_entity("minecraft:sign", None, Some("north")), Some(SignKind::Wall));
+		assert_eq!(block_entity("minecraft:hanging_sign", Some(0), None), Some(SignKind::Hanging));
+		assert_eq!(block_entity("minecraft:hanging_sign", None, Some("east")), Some(SignKind::WallHanging));
+		assert_eq!(block_entity("somemod:oak_wall_sign", None, None), Some(SignKind::Wall));
+		assert_eq!(block_entity("minecraft:sign", None, None), None);
+	}
+
+	#[test]
+	fn oversized_back_lines_are_suspicious() {
+		let text = |line: &str| string(&format!(r#"{{"text":"{}"}}"#, line));
+		let chunk = compound(vec![("block_entities", fastnbt::Value::List(vec![
+			sign_1_20(0, 64, 0, [text("front"), text(""), text(""), text("")], [text(""), text(&"a".repeat(4096)), text(""), text("")]),
+		]))]);
+		let result = extract(region_with_chunk(&chunk), 3465, "1.20.1");
+		let suspicious = suspicious_lines(&result.signs[0]);
+		assert_eq!(suspicious.len(), 1);
+		assert_eq!((suspicious[0].line, suspicious[0].back), (2, true));
+	}
+
+	#[test]
+	fn flat_signs_have_their_back_lines_after_the_front() {
+		let text = |line: &str| string(&format!(r#"{{"text":"{}"}}"#, line));
+		let chunk = compound(vec![("block_entities", fastnbt::Value::List(vec![
+			sign_1_20(0, 64, 0, [text("front"), text(""), text(""), text("")], [text("back"), text(""), text(""), text("end")]),
+			sign_1_20(1, 64, 0, [text("only"), text(""), text(""), text("")], [text(""), text(""), text(""), text("")]),
+		]))]);
+		let version = LevelDatDataVersion { id: 3465, name: "1.20.1".to_string(), snapshot: false };
+		let opts = Opts::parse_from(["mc-sign-extractor", "--save", "world", "--flat"]);
+		let mut flat = Vec::new();
+		write_signs_text(&mut flat, extract(region_with_chunk(&chunk), 3465, "1.20.1").signs, &version, &opts);
+		assert_eq!(String::from_utf8(flat).unwrap(), "0 64 0 | front |  |  |  | back: back |  |  | end\n1 64 0 | only |  |  | \n");
+	}
+
+	#[test]
+	fn flat_fields_escape_the_delimiter() {
+		assert_eq!(flat_field("a|b\\c\nd"), "a\\|b\\\\c\\nd");
+	}
+
+	#[test]
+	fn formatting_codes_are_stripped_in_any_case() {
+		assert_eq!(strip_formatting("§4old §Lbold§r §xstray"), "old bold xstray");
+	}
+}
@@ -0,0 +1,144 @@
+use crate::types::*;
+use crate::version::*;
+
+// fill in the rotation or facing of the signs of a chunk from the block states of its sections
+// the block entity of a sign doesn't store which way it faces, only the block does
+pub fn read_sign_orientations(buf: &[u8], version: &LevelDatDataVersion, signs: &mut [ChunkLevelTileEntities]) -> fastnbt::error::Result<()> {
+	if chunk_format_of(version) == ChunkFormat::V1_18 {
+		let chunk: ChunkSections1_18 = fastnbt::from_bytes(buf)?;
+		for sign in signs {
+			let section = chunk.sections.iter().find(|section| i32::from(section.y) == sign.y.div_euclid(16));
+			if let Some(block_states) = section.and_then(|section| section.block_states.as_ref()) {
+				let data = block_states.data.as_deref().unwrap_or_default();
+				let palette_index = palette_index(data, block_states.palette.len(), block_index(sign), false);
+				if let Some(block_state) = block_states.palette.get(palette_index) {
+					apply_block_state(sign, block_state);
+				}
+			}
+		}
+		return Ok(());
+	}
+
+	let chunk: ChunkSections = fastnbt::from_bytes(buf)?;
+	if let (Some(blocks), Some(data)) = (&chunk.level.blocks, &chunk.level.data) {
+		for sign in signs {
+			let index = mcregion_block_index(sign);
+			if let (Some(block), Some(value)) = (blocks.get(index), nibble(data, index)) {
+				apply_numeric_block(sign, *block as u8, value);
+			}
+		}
+		return Ok(());
+	}
+	let layout = block_layout_of(version);
+	for sign in signs {
+		let section = match chunk.level.sections.iter().find(|section| i32::from(section.y) == sign.y.div_euclid(16)) {
+			Some(section) => section,
+			None => continue,
+		};
+		let index = block_index(sign);
+		if layout == BlockLayout::NumericIds {
+			if let (Some(blocks), Some(data)) = (&section.blocks, &section.data) {
+				if let (Some(block), Some(value)) = (blocks.get(index), nibble(data, index)) {
+					apply_numeric_block(sign, *block as u8, value);
+				}
+			}
+		} else if let (Some(palette), Some(block_states)) = (&section.palette, &section.block_states) {
+			let palette_index = palette_index(block_states, palette.len(), index, layout == BlockLayout::SpanningPalette);
+			if let Some(block_state) = palette.get(palette_index) {
+				apply_block_state(sign, block_state);
+			}
+		}
+	}
+	Ok(())
+}
+
+// index of the block of a block entity inside its 16x16x16 section
+fn block_index(sign: &ChunkLevelTileEntities) -> usize {
+	let x = sign.x.rem_euclid(16) as usize;
+	let y = sign.y.rem_euclid(16) as usize;
+	let z = sign.z.rem_euclid(16) as usize;
+	(y * 16 + z) * 16 + x
+}
+
+// index of the block of a block entity in a mcregion chunk, ordered by x, then z, then y
+fn mcregion_block_index(sign: &ChunkLevelTileEntities) -> usize {
+	let x = sign.x.rem_euclid(16) as usize;
+	let z = sign.z.rem_euclid(16) as usize;
+	(x * 16 + z) * 128 + sign.y.clamp(0, 127) as usize
+}
+
+// data values are stored as nibbles, the even index in the low half
+fn nibble(data: &[i8], index: usize) -> Option<u8> {
+	data.get(index / 2).map(|byte| (*byte as u8 >> ((index % 2) * 4)) & 0x0f)
+}
+
+// get an entry of the packed palette indices of a section
+// every index uses as many bits as the palette needs but at least 4
+fn palette_index(data: &[i64], palette_len: usize, index: usize, spanning: bool) -> usize {
+	if palette_len <= 1 || data.is_empty() {
+		return 0;
+	}
+	let bits = ((usize::BITS - (palette_len - 1).leading_zeros()) as usize).max(4);
+	let mask = (1u64 << bits) - 1;
+
+	if spanning {
+		// indices are packed back to back and can continue in the next long
+		let bit = index * bits;
+		let (long, offset) = (bit / 64, bit % 64);
+		let mut value = data.get(long).map_or(0, |long| *long as u64) >> offset;
+		if offset + bits > 64 {
+			value |= data.get(long + 1).map_or(0, |long| *long as u64) << (64 - offset);
+		}
+		(value & mask) as usize
+	} else {
+		// the leftover bits at the end of every long are unused
+		let per_long = 64 / bits;
+		let long = data.get(index / per_long).map_or(0, |long| *long as u64);
+		((long >> ((index % per_long) * bits)) & mask) as usize
+	}
+}
+
+fn apply_block_state(sign: &mut ChunkLevelTileEntities, block_state: &BlockState) {
+	// the block at the position should be the sign, anything else means the chunk is inconsistent
+	if !block_state.name.to_lowercase().ends_with("sign") {
+		return;
+	}
+	sign.rotation = block_state.properties.get("rotation").and_then(|rotation| rotation.parse().ok());
+	sign.facing = block_state.properties.get("facing").cloned();
+}
+
+// before 1.13 standing signs are block 63 with the rotation as data value
+// and wall signs are block 68 with the facing as data value
+fn apply_numeric_block(sign: &mut ChunkLevelTileEntities, block: u8, value: u8) {
+	match block {
+		63 => sign.rotation = Some(value),
+		68 => {
+			sign.facing = match value {
+				2 => Some("north".to_string()),
+				3 => Some("south".to_string()),
+				4 => Some("west".to_string()),
+				5 => Some("east".to_string()),
+				_ => None,
+			}
+		}
+		_ => {}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn palette_indices_are_unpacked_for_both_layouts() {
+		// 5 bit indices, the 13th index starts at bit 60 and continues in the second long
+		let spanning = [(0b1011i64 << 60), 0b1];
+		assert_eq!(palette_index(&spanning, 17, 12, true), 0b11011);
+
+		// without spanning only 12 indices fit in a long so the 13th starts the second long
+		let packed = [0, 0b11011];
+		assert_eq!(palette_index(&packed, 17, 12, false), 0b11011);
+
+		assert_eq!(palette_index(&[], 1, 100, false), 0);
+	}
+}
@@ -0,0 +1,56 @@
+use std::sync::{Arc, Condvar, Mutex};
+
+// limits how many region files the reader threads of --threads-io keep in memory before they are parsed
+// without it readers that are faster than the parse threads would load the whole world into memory
+pub struct ReadAhead {
+	free: Mutex<usize>,
+	freed: Condvar,
+}
+
+// a region that was read and is waiting to be parsed, gives its place back when dropped after parsing
+pub struct ReadAheadPermit(Arc<ReadAhead>);
+
+impl ReadAhead {
+	pub fn new(regions: usize) -> Arc<ReadAhead> {
+		Arc::new(ReadAhead {
+			free: Mutex::new(regions.max(1)),
+			freed: Condvar::new(),
+		})
+	}
+
+	// wait until another region can be read
+	pub fn acquire(self: &Arc<ReadAhead>) -> ReadAheadPermit {
+		let mut free = self.free.lock().unwrap();
+		while *free == 0 {
+			free = self.freed.wait(free).unwrap();
+		}
+		*free -= 1;
+		ReadAheadPermit(self.clone())
+	}
+}
+
+impl Drop for ReadAheadPermit {
+	fn drop(&mut self) {
+		*self.0.free.lock().unwrap() += 1;
+		self.0.freed.notify_one();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn permits_are_given_back_when_dropped() {
+		let read_ahead = ReadAhead::new(1);
+		let permit = read_ahead.acquire();
+		let waiting = {
+			let read_ahead = read_ahead.clone();
+			std::thread::spawn(move || drop(read_ahead.acquire()))
+		};
+		std::thread::sleep(std::time::Duration::from_millis(50));
+		assert!(!waiting.is_finished());
+		drop(permit);
+		waiting.join().unwrap();
+	}
+}
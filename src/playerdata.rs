@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::path::Path;
+use flate2::read::GzDecoder;
+use log::{debug, warn};
+use crate::types::*;
+
+// collect the books players carry in their inventory and ender chest
+// these never show up in the region files unless the book is put down somewhere
+pub fn read_player_books(save_path: &Path, book_tag_path: Option<&str>) -> Vec<PlayerBook> {
+	let playerdata_path = save_path.join("playerdata");
+	// every player has a <uuid>.dat, the game also keeps a <uuid>.dat_old backup which is skipped
+	let mut names: Vec<String> = match playerdata_path.read_dir() {
+		Ok(files) => files
+			.filter_map(|file| file.ok()?.file_name().into_string().ok())
+			.filter(|name| name.ends_with(".dat"))
+			.collect(),
+		Err(_e) => {
+			warn!("{} does not exist", playerdata_path.display());
+			return Vec::new();
+		}
+	};
+	names.sort();
+
+	let user_cache = read_user_cache(save_path);
+	let mut books = Vec::new();
+	for name in names {
+		let file = match crate::open_shared(&playerdata_path.join(&name)) {
+			Ok(file) => file,
+			Err(e) => {
+				warn!("failed to open {}: {}", name, e);
+				continue;
+			}
+		};
+		let player: PlayerData = match fastnbt::from_reader(GzDecoder::new(file)) {
+			Ok(player) => player,
+			Err(e) => {
+				warn!("failed to read {}: {}", name, e);
+				continue;
+			}
+		};
+		let uuid = name.trim_end_matches(".dat");
+		for (inventory, items) in [("inventory", &player.inventory), ("ender chest", &player.ender_items)] {
+			for item in items.iter().filter_map(crate::item_of) {
+				let slot = item.slot;
+				if let Some(book) = crate::book_of(item, book_tag_path) {
+					books.push(PlayerBook {
+						uuid: uuid.to_string(),
+						name: user_cache.get(&uuid.to_lowercase()).cloned(),
+						inventory: inventory.to_string(),
+						slot,
+						book,
+					});
+				}
+			}
+		}
+	}
+	books
+}
+
+// the names of players by uuid
+// the server keeps usercache.json in its own folder, which is the parent of the world folder
+// but a world copied off a server sometimes has it next to level.dat instead
+fn read_user_cache(save_path: &Path) -> HashMap<String, String> {
+	let candidates = [save_path.parent().map(|parent| parent.join("usercache.json")), Some(save_path.join("usercache.json"))];
+	let path = match candidates.into_iter().flatten().find(|path| path.is_file()) {
+		Some(path) => path,
+		None => {
+			debug!("no usercache.json found, players are only named by uuid");
+			return HashMap::new();
+		}
+	};
+	let text = match std::fs::read_to_string(&path) {
+		Ok(text) => text,
+		Err(e) => {
+			warn!("failed to read {}: {}", path.display(), e);
+			return HashMap::new();
+		}
+	};
+	parse_user_cache(&text).unwrap_or_else(|e| {
+		warn!("failed to read {}: {}", path.display(), e);
+		HashMap::new()
+	})
+}
+
+fn parse_user_cache(text: &str) -> serde_json::Result<HashMap<String, String>> {
+	let entries: Vec<UserCacheEntry> = serde_json::from_str(text)?;
+	// uuids are written with dashes in both places but the case isn't guaranteed
+	Ok(entries.into_iter().map(|entry| (entry.uuid.to_lowercase(), entry.name)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn user_cache_names_players_by_uuid() {
+		let text = r#"[{"name":"Notch","uuid":"069A79F4-44E9-4726-A5BE-FCA90E38AAF5","expiresOn":"2030-01-01 00:00:00 +0000"}]"#;
+		let names = parse_user_cache(text).unwrap();
+		assert_eq!(names.get("069a79f4-44e9-4726-a5be-fca90e38aaf5").map(String::as_str), Some("Notch"));
+	}
+}
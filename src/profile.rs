@@ -0,0 +1,77 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use log::info;
+
+// the phases the time of a run is split into for --profile
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phase {
+	Reading,
+	Decompressing,
+	Parsing,
+	Writing,
+}
+
+impl Phase {
+	const ALL: [Phase; 4] = [Phase::Reading, Phase::Decompressing, Phase::Parsing, Phase::Writing];
+
+	fn name(self) -> &'static str {
+		match self {
+			Phase::Reading => "reading",
+			Phase::Decompressing => "decompressing",
+			Phase::Parsing => "nbt parsing",
+			Phase::Writing => "writing",
+		}
+	}
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+// nanoseconds spent in every phase, added to by all threads
+static TOTALS: [AtomicU64; 4] = [AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0), AtomicU64::new(0)];
+
+// start measuring, until this is called timers do nothing so there is no cost without --profile
+pub fn enable_profiling() {
+	ENABLED.store(true, Ordering::Relaxed);
+}
+
+// adds the time from its creation until it is dropped to a phase
+pub struct PhaseTimer {
+	phase: Phase,
+	started: Option<Instant>,
+}
+
+impl PhaseTimer {
+	pub fn start(phase: Phase) -> PhaseTimer {
+		let started = if ENABLED.load(Ordering::Relaxed) {
+			Some(Instant::now())
+		} else {
+			None
+		};
+		PhaseTimer { phase, started }
+	}
+}
+
+impl Drop for PhaseTimer {
+	fn drop(&mut self) {
+		if let Some(started) = self.started {
+			let elapsed = started.elapsed().as_nanos() as u64;
+			TOTALS[self.phase as usize].fetch_add(elapsed, Ordering::Relaxed);
+		}
+	}
+}
+
+// time a single expression
+pub fn time_phase<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+	let _timer = PhaseTimer::start(phase);
+	f()
+}
+
+// print how long every phase took
+// the phases run on all threads at once so together they can take longer than the whole run
+pub fn report_profile(total: Duration) {
+	info!("profile: total {:.3}s", total.as_secs_f64());
+	for phase in Phase::ALL {
+		let spent = Duration::from_nanos(TOTALS[phase as usize].load(Ordering::Relaxed));
+		info!("profile: {:<13} {:>9.3}s", phase.name(), spent.as_secs_f64());
+	}
+	info!("profile: times are added up over all threads, compare them to each other rather than to the total");
+}
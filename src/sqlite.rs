@@ -0,0 +1,114 @@
+use std::path::Path;
+use rusqlite::{params, Connection};
+use crate::types::*;
+
+// the text of signs and books is also put in fts5 tables that share the ids of the records
+// so something like "select * from signs where id in (select rowid from signs_fts where signs_fts match 'diamond')" is instant
+const SCHEMA: &str = "
+	create table signs (
+		id integer primary key,
+		dimension text not null,
+		x integer not null,
+		y integer not null,
+		z integer not null,
+		line1 text not null,
+		line2 text not null,
+		line3 text not null,
+		line4 text not null,
+		kind text,
+		rotation integer,
+		facing text
+	);
+	create table books (
+		id integer primary key,
+		dimension text not null,
+		x integer not null,
+		y integer not null,
+		z integer not null,
+		title text,
+		author text,
+		custom_name text
+	);
+	create table book_pages (
+		book_id integer not null references books(id),
+		page integer not null,
+		text text not null
+	);
+	create index signs_position on signs (dimension, x, z);
+	create index books_position on books (dimension, x, z);
+	create virtual table signs_fts using fts5 (text);
+	create virtual table books_fts using fts5 (title, author, text);
+";
+
+// write signs and books to a new sqlite database, an existing database at the path is replaced
+pub fn write_sqlite(path: &Path, signs: &[SignRecord], books: &[BookRecord]) -> rusqlite::Result<()> {
+	if path.exists() {
+		std::fs::remove_file(path).expect("failed to remove old database");
+	}
+	let mut connection = Connection::open(path)?;
+	connection.execute_batch(SCHEMA)?;
+
+	// a single transaction is a lot faster than committing every insert
+	let transaction = connection.transaction()?;
+	{
+		let mut insert_sign = transaction.prepare("insert into signs (dimension, x, y, z, line1, line2, line3, line4, kind, rotation, facing) values (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)")?;
+		let mut insert_sign_text = transaction.prepare("insert into signs_fts (rowid, text) values (?1, ?2)")?;
+		for sign in signs {
+			let line = |index: usize| sign.lines.get(index).map(String::as_str).unwrap_or_default();
+			let id = insert_sign.insert(params![sign.dimension.name(), sign.x, sign.y, sign.z, line(0), line(1), line(2), line(3), sign.kind.map(SignKind::name), sign.rotation, sign.facing])?;
+			// the back of 1.20 signs can be searched too
+			let text: Vec<&str> = sign.lines.iter().chain(&sign.back_lines).map(String::as_str).collect();
+			insert_sign_text.execute(params![id, text.join("\n")])?;
+		}
+
+		let mut insert_book = transaction.prepare("insert into books (dimension, x, y, z, title, author, custom_name) values (?1, ?2, ?3, ?4, ?5, ?6, ?7)")?;
+		let mut insert_page = transaction.prepare("insert into book_pages (book_id, page, text) values (?1, ?2, ?3)")?;
+		let mut insert_book_text = transaction.prepare("insert into books_fts (rowid, title, author, text) values (?1, ?2, ?3, ?4)")?;
+		for book in books {
+			let id = insert_book.insert(params![book.dimension.name(), book.x, book.y, book.z, book.title, book.author, book.custom_name])?;
+			for (page, text) in book.pages.iter().enumerate() {
+				insert_page.execute(params![id, page + 1, text])?;
+			}
+			insert_book_text.execute(params![id, book.title, book.author, book.pages.join("\n")])?;
+		}
+	}
+	transaction.commit()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::BTreeMap;
+
+	#[test]
+	fn sign_text_can_be_searched() {
+		let path = std::env::temp_dir().join("mc-sign-extractor-test.sqlite");
+		let sign = SignRecord {
+			dimension: Dimension::Nether,
+			x: 1,
+			y: -60,
+			z: 3,
+			lines: vec!["diamonds".to_string(), "this way".to_string(), String::new(), String::new()],
+			back_lines: Vec::new(),
+			color: None,
+			back_color: None,
+			waxed: false,
+			kind: Some(SignKind::Standing),
+			rotation: Some(4),
+			facing: None,
+			last_modified: None,
+			raw_text: BTreeMap::new(),
+			provenance: None,
+		};
+		write_sqlite(&path, &[sign], &[]).unwrap();
+
+		let connection = Connection::open(&path).unwrap();
+		let (dimension, y, kind): (String, i32, String) = connection.query_row(
+			"select dimension, y, kind from signs where id in (select rowid from signs_fts where signs_fts match 'diamonds')",
+			[],
+			|row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+		).unwrap();
+		assert_eq!((dimension.as_str(), y, kind.as_str()), ("the_nether", -60, "standing"));
+		std::fs::remove_file(&path).unwrap();
+	}
+}
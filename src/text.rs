@@ -0,0 +1,55 @@
+// sign lines and book pages are stored as json text components since 1.8
+// like {"text":"a","extra":[{"text":"b","color":"red"}]}, these helpers turn them into plain text
+
+// the version id to pass for worlds from before data versions, their signs store raw text instead of json
+pub const RAW_TEXT_VERSION: i32 = 0;
+
+// get the plain text of a sign line the way the game shows it
+// version_id is the data version of the world or RAW_TEXT_VERSION for worlds without one
+pub fn reconstruct_sign_text(raw: &str, version_id: i32) -> String {
+	if version_id == RAW_TEXT_VERSION {
+		return raw.to_string();
+	}
+	component_text(raw)
+}
+
+// get the plain text of a json text component like a sign line or book page
+// text that isn't valid json is returned as is
+pub fn component_text(raw: &str) -> String {
+	match serde_json::from_str(raw) {
+		Ok(value) => value_text(&value),
+		Err(_e) => raw.to_string(),
+	}
+}
+
+// flatten a parsed text component into plain text
+// a component is either a string, a list of components or an object with text and extra components
+pub fn value_text(value: &serde_json::Value) -> String {
+	match value {
+		serde_json::Value::String(text) => text.clone(),
+		serde_json::Value::Array(values) => values.iter().map(value_text).collect(),
+		serde_json::Value::Object(object) => {
+			let mut text = object.get("text").map(value_text).unwrap_or_default();
+			if let Some(extra) = object.get("extra") {
+				text.push_str(&value_text(extra));
+			}
+			text
+		}
+		serde_json::Value::Number(number) => number.to_string(),
+		serde_json::Value::Bool(value) => value.to_string(),
+		serde_json::Value::Null => String::new(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn extra_components_are_flattened() {
+		assert_eq!(reconstruct_sign_text(r#"{"text":"a","extra":[{"text":"b","extra":["c"]},"d"]}"#, 2975), "abcd");
+		assert_eq!(reconstruct_sign_text(r#""plain""#, 2975), "plain");
+		assert_eq!(reconstruct_sign_text("not json", 2975), "not json");
+		assert_eq!(reconstruct_sign_text(r#"{"text":"raw"}"#, RAW_TEXT_VERSION), r#"{"text":"raw"}"#);
+	}
+}
@@ -1,4 +1,5 @@
 
+use std::collections::{BTreeMap, HashMap};
 use serde::{Deserialize, Serialize};
 
 
@@ -13,7 +14,33 @@ pub struct LevelDatData {
 	#[serde(rename = "Version")]
 	pub version: Option<LevelDatDataVersion>,
 	#[serde(rename = "version")]
-	pub old_version: i32
+	pub old_version: i32,
+	// 1.16 moved the seed into WorldGenSettings
+	#[serde(rename = "RandomSeed")]
+	pub random_seed: Option<i64>,
+	#[serde(rename = "WorldGenSettings")]
+	pub world_gen_settings: Option<LevelDatWorldGenSettings>,
+	#[serde(rename = "SpawnX")]
+	pub spawn_x: Option<i32>,
+	#[serde(rename = "SpawnY")]
+	pub spawn_y: Option<i32>,
+	#[serde(rename = "SpawnZ")]
+	pub spawn_z: Option<i32>,
+}
+
+impl LevelDatData {
+	pub fn seed(&self) -> Option<i64> {
+		self.world_gen_settings.as_ref().map(|settings| settings.seed).or(self.random_seed)
+	}
+
+	pub fn spawn(&self) -> Option<(i32, i32, i32)> {
+		Some((self.spawn_x?, self.spawn_y?, self.spawn_z?))
+	}
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LevelDatWorldGenSettings {
+	pub seed: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -26,6 +53,38 @@ pub struct LevelDatDataVersion {
 	pub snapshot: bool,
 }
 
+// the nether and the end keep their own region folders in DIM-1 and DIM1
+// the same region file name exists in every dimension so anything keyed by position has to include it
+#[derive(clap::ValueEnum, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Dimension {
+	#[default]
+	#[serde(rename = "overworld")]
+	Overworld,
+	#[serde(rename = "the_nether")]
+	Nether,
+	#[serde(rename = "the_end")]
+	End,
+}
+
+impl Dimension {
+	pub fn name(self) -> &'static str {
+		match self {
+			Dimension::Overworld => "overworld",
+			Dimension::Nether => "the_nether",
+			Dimension::End => "the_end",
+		}
+	}
+
+	// get a folder of the dimension like region or poi
+	pub fn folder(self, world: &std::path::Path, folder: &str) -> std::path::PathBuf {
+		match self {
+			Dimension::Overworld => world.join(folder),
+			Dimension::Nether => world.join("DIM-1").join(folder),
+			Dimension::End => world.join("DIM1").join(folder),
+		}
+	}
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Chunk {
 	#[serde(rename = "Level")]
@@ -34,16 +93,17 @@ pub struct Chunk {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChunkLevel {
-	#[serde(rename = "TileEntities")]
+	// cubic chunks cubes without any tile entities or entities leave these out
+	#[serde(rename = "TileEntities", default)]
 	pub tile_entities: Vec<ChunkLevelTileEntities>,
-	#[serde(rename = "Entities")]
+	#[serde(rename = "Entities", default)]
 	pub entities: Vec<Entity>
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Entity {
 	#[serde(rename = "id")]
-	id: String,
+	pub id: String,
 	#[serde(rename = "Pos")]
 	pub pos: Vec<f64>,
 	#[serde(rename = "Item")]
@@ -69,26 +129,104 @@ pub struct ChunkLevelTileEntities {
 	pub text3: Option<String>,
 	#[serde(rename = "Text4")]
 	pub text4: Option<String>,
+	// 1.20 moved the lines into front_text and added a back side
+	// the front lines are copied into Text1-4 when the sign is read
+	#[serde(rename = "front_text")]
+	pub front_text: Option<SignSide>,
+	#[serde(rename = "back_text")]
+	pub back_text: Option<SignSide>,
+	// signs before 1.20 have a single color, kept as raw nbt since mods use the name for numeric colors
+	#[serde(rename = "Color")]
+	pub color: Option<fastnbt::Value>,
+	// waxed 1.20 signs can't be edited anymore
+	#[serde(rename = "is_waxed")]
+	pub is_waxed: Option<i8>,
+	// chests, barrels, hoppers, dispensers, droppers, shulker boxes, furnaces and chiseled bookshelves
 	#[serde(rename = "Items")]
 	pub items: Option<Vec<Item>>,
+	// decorated pots and suspicious sand and gravel hold a single item
+	// kept as raw nbt so a mod using the name for something else doesn't break the chunk
+	#[serde(rename = "item")]
+	pub item: Option<fastnbt::Value>,
+	// a lot of modded containers use Inventory, either as the list of items or as a compound with Items in it
+	#[serde(rename = "Inventory")]
+	pub inventory: Option<fastnbt::Value>,
+	// the music disc in a jukebox
+	#[serde(rename = "RecordItem")]
+	pub record_item: Option<fastnbt::Value>,
+	// Command is for command blocks
+	#[serde(rename = "Command")]
+	pub command: Option<String>,
+	// Book is for lecterns
+	#[serde(rename = "Book")]
+	pub book: Option<Item>,
+	// jigsaw blocks (name, target, pool, final_state, joint) and structure blocks (name, author, metadata, mode)
+	// kept as raw nbt so a mod using the same names for something else doesn't break the chunk
+	#[serde(rename = "name")]
+	pub name: Option<fastnbt::Value>,
+	#[serde(rename = "target")]
+	pub target: Option<fastnbt::Value>,
+	#[serde(rename = "pool")]
+	pub pool: Option<fastnbt::Value>,
+	#[serde(rename = "final_state")]
+	pub final_state: Option<fastnbt::Value>,
+	#[serde(rename = "joint")]
+	pub joint: Option<fastnbt::Value>,
+	#[serde(rename = "author")]
+	pub author: Option<fastnbt::Value>,
+	#[serde(rename = "metadata")]
+	pub metadata: Option<fastnbt::Value>,
+	#[serde(rename = "mode")]
+	pub mode: Option<fastnbt::Value>,
+	// orientation of signs, read from the block state since the block entity doesn't store it
+	// rotation (0-15) is for standing signs and facing is for wall signs
+	#[serde(skip)]
+	pub rotation: Option<u8>,
+	#[serde(skip)]
+	pub facing: Option<String>,
+	// the dimension the block entity was found in
+	#[serde(skip)]
+	pub dimension: Dimension,
+	// unix time the chunk was last saved, only read with --timestamps
+	#[serde(skip)]
+	pub last_modified: Option<u32>,
+	// the chunk format the block entity was read with
+	#[serde(skip)]
+	pub chunk_format: Option<crate::version::ChunkFormat>,
 }
 
+// one side of a 1.20 sign
 #[derive(Debug, Serialize, Deserialize)]
+pub struct SignSide {
+	// json strings until 1.21.5 which stores the text components as nbt instead
+	#[serde(rename = "messages", default)]
+	pub messages: Vec<fastnbt::Value>,
+	// the dye the side was colored with, black when it wasn't dyed
+	#[serde(rename = "color")]
+	pub color: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Item {
 	#[serde(rename = "id")]
 	pub id: String,
 	#[serde(rename = "Slot")]
-	slot: Option<i8>,
+	pub slot: Option<i8>,
 	#[serde(rename = "Count")]
-	count: i8,
+	pub count: i8,
 	#[serde(rename = "tag")]
-	pub tag: Option<Book>
+	pub tag: Option<ItemTag>
 }
 
+// the chunk structs only name the fields that are needed
+// fastnbt skips every other field like sections, heightmaps and biomes without reading it into memory
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Chunk1_18 {
 	#[serde(rename = "block_entities")]
-	pub block_entities: Vec<ChunkLevelTileEntities>
+	pub block_entities: Option<Vec<ChunkLevelTileEntities>>,
+	// chunks that weren't saved again since the world was upgraded to 1.18 still use the old layout
+	#[serde(rename = "Level")]
+	pub level: Option<ChunkLevel>,
 }
 
 // 1.17 remove Entities from chunk and put it in a separate file
@@ -101,10 +239,110 @@ pub struct Chunk1_17 {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Chunk1_17Level {
-	#[serde(rename = "TileEntities")]
+	#[serde(rename = "TileEntities", default)]
 	pub block_entities: Vec<ChunkLevelTileEntities>,
 }
 
+// the block entities of a chunk as raw nbt for --dump-raw-signs
+// only the block entities are read into fastnbt::Value, not the whole chunk
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RawChunk1_18 {
+	#[serde(rename = "block_entities")]
+	pub block_entities: Option<Vec<fastnbt::Value>>,
+	#[serde(rename = "Level")]
+	pub level: Option<RawChunkLevel>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RawChunk {
+	#[serde(rename = "Level")]
+	pub level: RawChunkLevel
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RawChunkLevel {
+	#[serde(rename = "TileEntities", default)]
+	pub tile_entities: Vec<fastnbt::Value>,
+	#[serde(rename = "Entities", default)]
+	pub entities: Vec<fastnbt::Value>,
+}
+
+// a chunk of the entities folder from 1.17 on
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntityChunk {
+	#[serde(rename = "Entities")]
+	pub entities: Option<Vec<Entity>>,
+	#[serde(rename = "Level")]
+	pub level: Option<EntityChunkLevel>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntityChunkLevel {
+	#[serde(rename = "Entities", default)]
+	pub entities: Vec<Entity>,
+}
+
+// 1.18+ chunk sections, only read for chunks that contain signs
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChunkSections1_18 {
+	#[serde(default)]
+	pub sections: Vec<Section1_18>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Section1_18 {
+	#[serde(rename = "Y")]
+	pub y: i8,
+	pub block_states: Option<BlockStates>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockStates {
+	pub palette: Vec<BlockState>,
+	// left out when the palette only has one entry
+	pub data: Option<fastnbt::LongArray>,
+}
+
+// sections before 1.18, either with a palette (1.13+) or with numeric block ids
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChunkSections {
+	#[serde(rename = "Level")]
+	pub level: ChunkSectionsLevel,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChunkSectionsLevel {
+	#[serde(rename = "Sections", default)]
+	pub sections: Vec<Section>,
+	// mcregion chunks have no sections, the blocks of the whole 128 block high chunk are in one array
+	#[serde(rename = "Blocks")]
+	pub blocks: Option<fastnbt::ByteArray>,
+	#[serde(rename = "Data")]
+	pub data: Option<fastnbt::ByteArray>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Section {
+	#[serde(rename = "Y")]
+	pub y: i8,
+	#[serde(rename = "Palette")]
+	pub palette: Option<Vec<BlockState>>,
+	#[serde(rename = "BlockStates")]
+	pub block_states: Option<fastnbt::LongArray>,
+	// before 1.13 blocks are stored as numeric ids with a 4 bit data value
+	#[serde(rename = "Blocks")]
+	pub blocks: Option<fastnbt::ByteArray>,
+	#[serde(rename = "Data")]
+	pub data: Option<fastnbt::ByteArray>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockState {
+	#[serde(rename = "Name")]
+	pub name: String,
+	#[serde(rename = "Properties", default)]
+	pub properties: HashMap<String, String>,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SignExtra {
@@ -115,12 +353,35 @@ pub struct SignExtra {
 	underlined: Option<bool>, // if true then the text is underlined
 	strikethrough: Option<bool>, // if true then the text is crossed out
 	obfuscated: Option<bool>, // if true then the text is randomly scrambled every time it is displayed
+	#[serde(rename = "clickEvent")]
+	pub click_event: Option<ClickEvent>, // action run when the text is clicked
+	#[serde(rename = "hoverEvent")]
+	pub hover_event: Option<HoverEvent>, // tooltip shown when the text is hovered
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SignText {
 	pub text: String,
 	pub extra: Option<Vec<SignExtra>>,
+	#[serde(rename = "clickEvent")]
+	pub click_event: Option<ClickEvent>,
+	#[serde(rename = "hoverEvent")]
+	pub hover_event: Option<HoverEvent>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClickEvent {
+	pub action: String, // e.g. run_command, open_url
+	pub value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HoverEvent {
+	pub action: String, // e.g. show_text, show_item
+	// before 1.16 the tooltip is stored in value, after it is stored in contents
+	// both can be a plain string or a json text component
+	pub value: Option<serde_json::Value>,
+	pub contents: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -131,11 +392,505 @@ pub struct Book {
 	pub title: Option<String>,
 	#[serde(rename = "author")]
 	pub author: Option<String>,
+	// the name the book was given in an anvil, read from display.Name of the item
+	#[serde(skip)]
+	pub custom_name: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BookWithPos {
 	pub book: Book,
+	pub dimension: Dimension,
+	pub x: i32,
+	pub y: i32,
+	pub z: i32,
+	// unix time the chunk was last saved, only read with --timestamps
+	#[serde(default)]
+	pub last_modified: Option<u32>,
+	// where the same book was also found, only with --dedup-books
+	#[serde(default)]
+	pub copies: Vec<Location>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Location {
+	pub dimension: Dimension,
+	pub x: i32,
+	pub y: i32,
+	pub z: i32,
+}
+
+// everything extracted from a single region file
+#[derive(Debug, Default)]
+pub struct RegionResult {
+	pub signs: Vec<ChunkLevelTileEntities>,
+	pub books: Vec<BookWithPos>,
+	pub commands: Vec<CommandWithPos>,
+	pub chunks: Vec<ChunkCount>,
+	pub raw_signs: Vec<RawSign>,
+	pub regions: Vec<RegionInfo>,
+	pub structures: Vec<StructureWithPos>,
+	pub containers: Vec<ContainerWithPos>,
+	// how many block entities of every id there are, only counted with --list-block-entities
+	pub block_entity_ids: HashMap<String, usize>,
+	// chunks (or cubes) of the region that have data and how many of them couldn't be read
+	pub chunks_with_data: usize,
+	pub failed_chunks: usize,
+	// the chunk x and z of the chunks that couldn't be read, so --live can read them again
+	pub failed_chunk_positions: Vec<(i32, i32)>,
+	// everything that couldn't be read, for --error-report
+	pub errors: Vec<ReadError>,
+}
+
+// a jigsaw or structure block with its string fields, for --structures
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StructureWithPos {
+	pub block: String, // jigsaw or structure_block
+	pub dimension: Dimension,
+	pub x: i32,
+	pub y: i32,
+	pub z: i32,
+	pub fields: Vec<(String, String)>, // name and value of every string field that is set
+}
+
+// the fields of an item's tag that books and containers use
+// the tag is read as raw nbt first and the fields are taken out of it since their types changed between versions,
+// the raw tag is kept because mods store the pages of their books in different places for --book-tag-path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "fastnbt::Value", into = "fastnbt::Value")]
+pub struct ItemTag {
+	// written books and book and quills
+	pub pages: Option<Vec<String>>,
+	pub title: Option<String>,
+	pub author: Option<String>,
+	pub display: ItemDisplay,
+	// Enchantments, StoredEnchantments of enchanted books and ench before 1.13
+	pub enchantments: Vec<Enchantment>,
+	// the items in BlockEntityTag of shulker boxes and other blocks that keep them when broken
+	pub block_entity_items: Vec<Item>,
+	pub raw: fastnbt::Value,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ItemDisplay {
+	pub name: Option<String>,
+	pub lore: Vec<String>,
+}
+
+// a chest or other block entity with items and everything it holds, for --containers
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContainerWithPos {
+	pub block: String,
+	pub dimension: Dimension,
+	pub x: i32,
+	pub y: i32,
+	pub z: i32,
+	pub items: Vec<ContainerItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ContainerItem {
+	pub id: String,
+	pub count: i8,
+	pub slot: Option<i8>,
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub custom_name: Option<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty", default)]
+	pub lore: Vec<String>,
+	#[serde(skip_serializing_if = "Vec::is_empty", default)]
+	pub enchantments: Vec<Enchantment>,
+	// the items in a shulker box
+	#[serde(skip_serializing_if = "Vec::is_empty", default)]
+	pub contents: Vec<ContainerItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Enchantment {
+	pub id: String, // a number before 1.13
+	pub level: i64,
+}
+
+// the root of the files in the data folder
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DataFile {
+	pub data: fastnbt::Value,
+}
+
+// a string found in command storage or the scoreboard, for --data-text
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DataText {
+	pub file: String,
+	pub path: String, // like data.contents.lore[0]
+	pub text: String,
+}
+
+// the items of a player in playerdata/<uuid>.dat
+// items are kept as raw nbt since their layout changed between versions and mods add their own
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlayerData {
+	#[serde(rename = "Inventory", default)]
+	pub inventory: Vec<fastnbt::Value>,
+	#[serde(rename = "EnderItems", default)]
+	pub ender_items: Vec<fastnbt::Value>,
+}
+
+// a player the server has seen, from the usercache.json next to the world
+// expiresOn is left out since an expired name is still the best guess there is
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UserCacheEntry {
+	pub name: String,
+	pub uuid: String,
+}
+
+// a book carried by a player, for --player-books
+#[derive(Debug)]
+pub struct PlayerBook {
+	pub uuid: String,
+	pub name: Option<String>, // from usercache.json
+	pub inventory: String, // inventory or ender chest
+	pub slot: Option<i8>,
+	pub book: Book,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlayerBookRecord {
+	pub uuid: String,
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub name: Option<String>,
+	pub inventory: String,
+	pub slot: Option<i8>,
+	pub title: Option<String>,
+	pub author: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub custom_name: Option<String>,
+	pub pages: Vec<String>,
+}
+
+// the size of the library of a world, for --book-stats
+#[derive(Debug, PartialEq)]
+pub struct BookStats {
+	pub books: usize,
+	pub authors: usize,
+	pub titles: usize,
+	pub pages: usize,
+}
+
+// a region file that was read, for --manifest
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegionInfo {
+	pub dimension: Dimension,
+	pub file: String,
+	pub size: u64, // size of the file in bytes
+	pub chunks: usize, // chunks that have data
+	pub failed_chunks: usize, // chunks that couldn't be decompressed or parsed
+	pub error: Option<String>, // why the whole file couldn't be read
+}
+
+// what kind of problem kept something from being read, for --error-report
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadErrorKind {
+	File, // the whole region file couldn't be opened or decompressed
+	Header, // the table of chunk locations at the start of the region
+	Io, // the file ended or failed in the middle of a chunk
+	InvalidLength,
+	UnsupportedCompression,
+	Decompression,
+	Nbt,
+	BlockStates, // the chunk was read but the sign orientations are missing
+}
+
+// something that couldn't be read, for --error-report
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReadError {
+	pub dimension: Dimension,
+	pub region: String, // file name of the region
+	pub chunk_x: Option<i32>, // none when it isn't about a single chunk or the chunk position isn't known, like for cubes
+	pub chunk_z: Option<i32>,
+	pub kind: ReadErrorKind,
+	pub message: String,
+}
+
+// everything extracted from a world, sorted by position
+#[derive(Debug)]
+pub struct World {
+	pub version: LevelDatDataVersion,
+	// not known when the version is forced and level.dat isn't read
+	pub seed: Option<i64>,
+	pub spawn: Option<(i32, i32, i32)>,
+	pub result: RegionResult,
+}
+
+// the untouched nbt of a sign for debugging
+#[derive(Debug)]
+pub struct RawSign {
+	pub dimension: Dimension,
+	pub x: i32,
+	pub y: i32,
+	pub z: i32,
+	pub nbt: fastnbt::Value,
+}
+
+// number of signs and books found in a chunk
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChunkCount {
+	pub dimension: Dimension,
+	pub chunk_x: i32,
+	pub chunk_z: i32,
+	pub signs: usize,
+	pub books: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommandWithPos {
+	pub command: String,
+	pub source: String, // "command_block" or "sign"
+	pub dimension: Dimension,
+	pub x: i32,
+	pub y: i32,
+	pub z: i32,
+}
+
+// a sign line that could crash clients, found with --detect-exploits
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct SuspiciousLine {
+	pub dimension: Dimension,
+	pub x: i32,
+	pub y: i32,
+	pub z: i32,
+	pub line: usize, // 1-4
+	// the line is on the back of a 1.20 sign
+	#[serde(skip_serializing_if = "std::ops::Not::not", default)]
+	pub back: bool,
+	pub length: usize, // length of the raw line in bytes
+	pub reason: String,
+}
+
+// how a sign line can be read, found with --validate
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineFormat {
+	Json,
+	Raw,
+	// looks like json but can't be parsed
+	Broken,
+}
+
+impl LineFormat {
+	pub fn name(self) -> &'static str {
+		match self {
+			LineFormat::Json => "json",
+			LineFormat::Raw => "raw",
+			LineFormat::Broken => "broken",
+		}
+	}
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct LineValidation {
+	pub dimension: Dimension,
+	pub x: i32,
+	pub y: i32,
+	pub z: i32,
+	pub line: usize, // 1-4
+	pub format: LineFormat,
+	pub text: String, // the line as it is stored
+}
+
+// signs and books in the form they are written to structured output
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SignRecord {
+	pub dimension: Dimension,
+	pub x: i32,
+	pub y: i32,
+	pub z: i32,
+	pub lines: Vec<String>,
+	// the back of a 1.20 sign, left out when nothing is written on it
+	#[serde(skip_serializing_if = "Vec::is_empty", default)]
+	pub back_lines: Vec<String>,
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub color: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub back_color: Option<String>,
+	#[serde(default)]
+	pub waxed: bool,
+	// left out when the block state of the sign couldn't be read
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub kind: Option<SignKind>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub rotation: Option<u8>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub facing: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub last_modified: Option<u32>,
+	// the lines that look like json but can't be read, as they are stored, by their name like text1 or back_text2
+	#[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+	pub raw_text: BTreeMap<String, String>,
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub provenance: Option<Provenance>,
+}
+
+// how a sign is placed, standing and hanging signs have a rotation and wall signs a facing
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignKind {
+	Standing,
+	Wall,
+	Hanging, // hanging from the ceiling
+	WallHanging,
+}
+
+impl SignKind {
+	pub fn name(self) -> &'static str {
+		match self {
+			SignKind::Standing => "standing",
+			SignKind::Wall => "wall",
+			SignKind::Hanging => "hanging",
+			SignKind::WallHanging => "wall_hanging",
+		}
+	}
+}
+
+// how a sign was read, to find out why some signs of a world look wrong
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Provenance {
+	pub chunk_format: String, // legacy, 1.17 or 1.18
+	pub text: String, // json, raw or json with raw fallback when some lines weren't json
+	pub dimension: Dimension,
+}
+
+// a sign with coordinates written on it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Waypoint {
+	pub dimension: Dimension,
+	pub x: i32,
+	pub y: i32,
+	pub z: i32,
+	pub target_x: i32,
+	// signs often only give the x and z coordinates
+	pub target_y: Option<i32>,
+	pub target_z: i32,
+}
+
+// signs and books written to a single file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Report {
+	pub signs: Vec<SignRecord>,
+	pub books: Vec<BookRecord>,
+}
+
+// geojson for gis tools, every sign or book is a point feature with its record as properties
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeatureCollection {
+	#[serde(rename = "type")]
+	pub kind: String, // always FeatureCollection
+	pub features: Vec<Feature>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Feature {
+	#[serde(rename = "type")]
+	pub kind: String, // always Feature
+	pub geometry: Point,
+	pub properties: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Point {
+	#[serde(rename = "type")]
+	pub kind: String, // always Point
+	pub coordinates: [i32; 2], // x and z
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BookRecord {
+	pub dimension: Dimension,
+	pub x: i32,
+	pub y: i32,
+	pub z: i32,
+	pub title: Option<String>,
+	pub author: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub custom_name: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none", default)]
+	pub last_modified: Option<u32>,
+	#[serde(skip_serializing_if = "Vec::is_empty", default)]
+	pub copies: Vec<Location>,
+	pub pages: Vec<String>,
+}
+
+// signs and books that differ between two copies of a world
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffReport {
+	pub signs: Vec<SignChange>,
+	pub books: Vec<BookChange>,
+}
+
+// the changes found after a save with --watch, written as one line of json
+#[derive(Debug, Serialize)]
+pub struct WatchReport<'a> {
+	pub time: u64, // unix time the changes were found
+	pub signs: &'a [SignChange],
+	pub books: &'a [BookChange],
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+	Added,
+	Removed,
+	Changed,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignChange {
+	pub change: Change,
+	pub dimension: Dimension,
+	pub x: i32,
+	pub y: i32,
+	pub z: i32,
+	pub old: Option<SignRecord>,
+	pub new: Option<SignRecord>,
+}
+
+// a container can hold more than one book so all books at a position are compared together
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BookChange {
+	pub change: Change,
+	pub dimension: Dimension,
+	pub x: i32,
+	pub y: i32,
+	pub z: i32,
+	pub old: Vec<BookRecord>,
+	pub new: Vec<BookRecord>,
+}
+
+// points of interest like villager workstations and beds are stored in the poi folder
+// every chunk has a section per 16 blocks of height, keyed by the section y
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PoiChunk {
+	#[serde(rename = "Sections", default)]
+	pub sections: HashMap<String, PoiSection>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PoiSection {
+	#[serde(rename = "Records", default)]
+	pub records: Vec<PoiRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PoiRecord {
+	#[serde(rename = "type")]
+	pub poi_type: String,
+	#[serde(rename = "pos")]
+	pub pos: fastnbt::IntArray,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PoiWithPos {
+	pub poi_type: String,
+	pub dimension: Dimension,
 	pub x: i32,
 	pub y: i32,
 	pub z: i32,
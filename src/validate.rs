@@ -0,0 +1,63 @@
+use crate::types::*;
+
+// find out how every line of a sign can be read, to check the guess between json and raw text made from the version
+// a line is json if it parses into a text component, raw if it doesn't look like json at all
+// and broken if it looks like json but can't be parsed, which neither way of reading shows correctly
+pub fn line_format(line: &str) -> Option<LineFormat> {
+	// empty lines are left out by some versions instead of being written as json
+	if line.is_empty() {
+		return None;
+	}
+	match serde_json::from_str::<serde_json::Value>(line) {
+		Ok(serde_json::Value::String(_) | serde_json::Value::Object(_) | serde_json::Value::Array(_)) => Some(LineFormat::Json),
+		// raw text like 5 or true is valid json but isn't a text component
+		Ok(_) => Some(LineFormat::Raw),
+		Err(_) if looks_like_json(line) => Some(LineFormat::Broken),
+		Err(_) => Some(LineFormat::Raw),
+	}
+}
+
+fn looks_like_json(line: &str) -> bool {
+	let line = line.trim();
+	line.starts_with('{') || line.starts_with('[') || (line.len() >= 2 && line.starts_with('"') && line.ends_with('"'))
+}
+
+// the format of every line of every sign that has text
+pub fn validate_signs(signs: &[ChunkLevelTileEntities]) -> Vec<LineValidation> {
+	let mut validations = Vec::new();
+	for sign in signs {
+		for (index, line) in [&sign.text1, &sign.text2, &sign.text3, &sign.text4].into_iter().enumerate() {
+			let line = match line {
+				Some(line) => line,
+				None => continue,
+			};
+			if let Some(format) = line_format(line) {
+				validations.push(LineValidation {
+					dimension: sign.dimension,
+					x: sign.x,
+					y: sign.y,
+					z: sign.z,
+					line: index + 1,
+					format,
+					text: line.clone(),
+				});
+			}
+		}
+	}
+	validations
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn lines_are_told_apart() {
+		assert_eq!(line_format(r#"{"text":"a"}"#), Some(LineFormat::Json));
+		assert_eq!(line_format(r#""quoted""#), Some(LineFormat::Json));
+		assert_eq!(line_format("plain text"), Some(LineFormat::Raw));
+		assert_eq!(line_format("42"), Some(LineFormat::Raw));
+		assert_eq!(line_format(r#"{"text":"cut off"#), Some(LineFormat::Broken));
+		assert_eq!(line_format(""), None);
+	}
+}
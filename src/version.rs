@@ -0,0 +1,144 @@
+use crate::types::LevelDatDataVersion;
+
+// data versions after which the chunk format changed
+// see https://minecraft.wiki/w/Data_version for the full list
+
+// 17w47a, the flattening replaced numeric block ids with block state palettes
+pub const DATA_VERSION_17W47A: i32 = 1451;
+// 20w17a, block states stopped spanning across longs
+pub const DATA_VERSION_20W17A: i32 = 2529;
+// 20w45a, the last snapshot before 1.17 moved entities out of the chunks
+pub const DATA_VERSION_20W45A: i32 = 2681;
+// 1.17.1, the last version with block entities under Level.TileEntities
+pub const DATA_VERSION_1_17_1: i32 = 2730;
+
+// the layout used to store block entities and entities in a chunk
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChunkFormat {
+	// Level.TileEntities and Level.Entities, used up to 1.16
+	Legacy,
+	// Level.TileEntities, entities are stored in the entities folder
+	V1_17,
+	// block_entities at the root of the chunk
+	V1_18,
+}
+
+impl ChunkFormat {
+	pub fn name(self) -> &'static str {
+		match self {
+			ChunkFormat::Legacy => "legacy",
+			ChunkFormat::V1_17 => "1.17",
+			ChunkFormat::V1_18 => "1.18",
+		}
+	}
+}
+
+// get the chunk format used by a data version
+pub fn chunk_format_for(id: i32) -> ChunkFormat {
+	if id > DATA_VERSION_1_17_1 {
+		ChunkFormat::V1_18
+	} else if id > DATA_VERSION_20W45A {
+		ChunkFormat::V1_17
+	} else {
+		ChunkFormat::Legacy
+	}
+}
+
+// get the chunk format of a world
+// worlds from before level.dat had a Version use the nbt version as id
+// which is higher than any data version so they have to be checked separately
+pub fn chunk_format_of(version: &LevelDatDataVersion) -> ChunkFormat {
+	if version.name == "old" {
+		ChunkFormat::Legacy
+	} else {
+		chunk_format_for(version.id)
+	}
+}
+
+// the way blocks are stored in chunk sections
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlockLayout {
+	// Blocks and Data arrays with numeric ids, used up to 1.12
+	NumericIds,
+	// palette indices that can be split across two longs, used from 1.13 to 1.15
+	SpanningPalette,
+	// palette indices that never cross a long
+	Palette,
+}
+
+// get the block layout of a world
+pub fn block_layout_of(version: &LevelDatDataVersion) -> BlockLayout {
+	if version.name == "old" || version.id < DATA_VERSION_17W47A {
+		BlockLayout::NumericIds
+	} else if version.id < DATA_VERSION_20W17A {
+		BlockLayout::SpanningPalette
+	} else {
+		BlockLayout::Palette
+	}
+}
+
+impl ChunkFormat {
+	fn description(self) -> &'static str {
+		match self {
+			ChunkFormat::Legacy => "block entities in Level.TileEntities and entities in Level.Entities",
+			ChunkFormat::V1_17 => "block entities in Level.TileEntities and entities in the entities folder",
+			ChunkFormat::V1_18 => "block entities in block_entities and entities in the entities folder",
+		}
+	}
+}
+
+impl BlockLayout {
+	fn description(self) -> &'static str {
+		match self {
+			BlockLayout::NumericIds => "numeric block ids",
+			BlockLayout::SpanningPalette => "block palettes with indices across longs",
+			BlockLayout::Palette => "block palettes",
+		}
+	}
+}
+
+// the versions the thresholds above are named after
+const THRESHOLD_NAMES: [(i32, &str); 4] = [
+	(DATA_VERSION_17W47A, "17w47a"),
+	(DATA_VERSION_20W17A, "20w17a"),
+	(DATA_VERSION_20W45A, "20w45a"),
+	(DATA_VERSION_1_17_1, "1.17.1"),
+];
+
+// describe the ranges of data versions that are read differently, for --list-versions
+// the ranges come from the same thresholds that pick the chunk format and block layout
+pub fn supported_versions() -> Vec<String> {
+	let label = |id: i32| match THRESHOLD_NAMES.iter().find(|(threshold, _)| *threshold == id) {
+		Some((_, name)) => format!("{} ({})", id, name),
+		None => id.to_string(),
+	};
+	let mut lines = vec![format!(
+		"no data version (1.8 and older, or --force-version 0): raw sign text, {}, {}",
+		ChunkFormat::Legacy.description(),
+		BlockLayout::NumericIds.description(),
+	)];
+	let starts = [1, DATA_VERSION_17W47A, DATA_VERSION_20W17A, DATA_VERSION_20W45A + 1, DATA_VERSION_1_17_1 + 1];
+	for (index, start) in starts.iter().enumerate() {
+		let range = match starts.get(index + 1) {
+			Some(next) => format!("{} to {}", label(*start), label(next - 1)),
+			None => format!("{} and newer", label(*start)),
+		};
+		let version = LevelDatDataVersion { id: *start, name: String::new(), snapshot: false };
+		lines.push(format!("{}: json sign text, {}, {}", range, chunk_format_for(*start).description(), block_layout_of(&version).description()));
+	}
+	lines
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn every_range_is_listed() {
+		let versions = supported_versions();
+		assert_eq!(versions.len(), 6);
+		assert!(versions[1].starts_with("1 to 1450:"));
+		assert!(versions[4].starts_with("2682 to 2730 (1.17.1): json sign text, block entities in Level.TileEntities and entities in the entities folder"));
+		assert!(versions[5].starts_with("2731 and newer:"));
+	}
+}
@@ -0,0 +1,185 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use log::{info, warn};
+use notify::{EventKind, RecursiveMode, Watcher};
+use crate::types::*;
+use crate::encoding::*;
+use crate::diff::*;
+use crate::{book_record, extract_signs_from_mca_file, interrupted, region_coordinates, replaced_by_anvil, sign_record, Format, Opts};
+
+// a region of a dimension, the region file and the entities file of the same region share it
+type RegionKey = (Dimension, i32, i32);
+
+// the signs and books of every region, changes are found by comparing a region with what it held before
+pub type RegionRecords = HashMap<RegionKey, (Vec<SignRecord>, Vec<BookRecord>)>;
+
+// how long the folders have to be quiet before the changed regions are read
+// the game saves a lot of regions one after the other so this reads every region once per save
+const SETTLE_DELAY: Duration = Duration::from_secs(2);
+
+// regions are 512 blocks wide
+pub fn region_records(signs: &[ChunkLevelTileEntities], books: &[BookWithPos], version: &LevelDatDataVersion, opts: &Opts) -> RegionRecords {
+	let mut records = RegionRecords::new();
+	for sign in signs {
+		records.entry((sign.dimension, sign.x.div_euclid(512), sign.z.div_euclid(512))).or_default().0.push(sign_record(sign, version, opts.line_options()));
+	}
+	for book in books {
+		records.entry((book.dimension, book.x.div_euclid(512), book.z.div_euclid(512))).or_default().1.push(book_record(book, opts.keep_formatting));
+	}
+	records
+}
+
+// watch the region and entities folders and write what changed in the regions that were saved
+// the changes are appended to watch-{save}.txt or watch-{save}.ndjson until ctrl-c is pressed
+pub fn watch_world(save_path: &Path, save_name: &str, version: &LevelDatDataVersion, mut records: RegionRecords, opts: &Opts) {
+	let (tx, rx) = std::sync::mpsc::channel();
+	let mut watcher = notify::recommended_watcher(tx).expect("failed to start watching");
+	let mut folders: HashMap<PathBuf, Dimension> = HashMap::new();
+	for dimension in &opts.dimensions {
+		for folder in ["region", "entities"] {
+			let path = dimension.folder(save_path, folder);
+			// a world without entities has no entities folder until one is saved
+			if path.is_dir() {
+				watcher.watch(&path, RecursiveMode::NonRecursive).expect("failed to watch folder");
+				folders.insert(path, *dimension);
+			}
+		}
+	}
+
+	let mut file = match opts.format {
+		Format::Json | Format::Geojson => EncodedFile::create(format!("watch-{save_name}.ndjson"), opts.output_encoding).unwrap(),
+		_ => EncodedFile::create(format!("watch-{save_name}.txt"), opts.output_encoding).unwrap(),
+	};
+	info!("watching {} for saved regions, press ctrl-c to stop", save_path.display());
+
+	let mut changed: HashSet<RegionKey> = HashSet::new();
+	let mut last_change = Instant::now();
+	loop {
+		match rx.recv_timeout(Duration::from_millis(500)) {
+			Ok(Ok(event)) => {
+				if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+					continue;
+				}
+				for path in event.paths {
+					let dimension = match path.parent().and_then(|folder| folders.get(folder)) {
+						Some(dimension) => *dimension,
+						None => continue,
+					};
+					if let Some((rx, rz)) = path.file_name().and_then(|name| name.to_str()).and_then(region_coordinates) {
+						changed.insert((dimension, rx, rz));
+						last_change = Instant::now();
+					}
+				}
+			}
+			Ok(Err(e)) => warn!("failed to watch {}: {}", save_path.display(), e),
+			Err(RecvTimeoutError::Timeout) => {
+				if interrupted() {
+					break;
+				}
+				if changed.is_empty() || last_change.elapsed() < SETTLE_DELAY {
+					continue;
+				}
+				let mut report = DiffReport { signs: Vec::new(), books: Vec::new() };
+				for key in changed.drain() {
+					let (signs, books) = read_region(save_path, key, version, opts);
+					let region_report = region_changes(&mut records, key, signs, books);
+					report.signs.extend(region_report.signs);
+					report.books.extend(region_report.books);
+				}
+				if report.signs.is_empty() && report.books.is_empty() {
+					continue;
+				}
+				info!("{} signs and {} book positions changed", report.signs.len(), report.books.len());
+				write_changes(&mut file, &report, opts);
+			}
+			Err(RecvTimeoutError::Disconnected) => break,
+		}
+	}
+}
+
+// read the region file and the entities file of a region again
+// the server is most likely still writing so chunks that fail are tried again like with --live
+fn read_region(save_path: &Path, (dimension, rx, rz): RegionKey, version: &LevelDatDataVersion, opts: &Opts) -> (Vec<SignRecord>, Vec<BookRecord>) {
+	let mut signs = Vec::new();
+	let mut books = Vec::new();
+	for (folder, entities) in [("region", false), ("entities", true)] {
+		let path = dimension.folder(save_path, folder);
+		// the file can also be compressed like r.0.0.mca.gz so it is found by its coordinates
+		let files = match path.read_dir() {
+			Ok(files) => files,
+			Err(_e) => continue,
+		};
+		for file in files.flatten() {
+			if file.file_name().to_str().and_then(region_coordinates) != Some((rx, rz)) || replaced_by_anvil(&file.path()) {
+				continue;
+			}
+			let mut options = opts.extract_options();
+			options.dimension = dimension;
+			options.entities = entities;
+			options.live = true;
+			let result = extract_signs_from_mca_file(file.path(), version.clone(), &options);
+			signs.extend(result.signs.iter()
+				.filter(|sign| opts.position_wanted(sign.x, sign.y, sign.z) && opts.sign_text_wanted(sign, version))
+				.map(|sign| sign_record(sign, version, opts.line_options())));
+			books.extend(result.books.iter()
+				.filter(|book| opts.position_wanted(book.x, book.y, book.z))
+				.map(|book| book_record(book, opts.keep_formatting)));
+		}
+	}
+	(signs, books)
+}
+
+// compare a region with what it held before and remember what it holds now
+fn region_changes(records: &mut RegionRecords, key: RegionKey, signs: Vec<SignRecord>, books: Vec<BookRecord>) -> DiffReport {
+	let (old_signs, old_books) = records.remove(&key).unwrap_or_default();
+	let report = DiffReport {
+		signs: diff_signs(old_signs, signs.clone()),
+		books: diff_books(old_books, books.clone()),
+	};
+	records.insert(key, (signs, books));
+	report
+}
+
+fn write_changes(file: &mut EncodedFile, report: &DiffReport, opts: &Opts) {
+	let time = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0);
+	match opts.format {
+		Format::Json | Format::Geojson => {
+			serde_json::to_writer(&mut *file, &WatchReport { time, signs: &report.signs, books: &report.books }).unwrap();
+			writeln!(file).unwrap();
+		}
+		_ => {
+			writeln!(file, "########## changes at {} ##########\n", time).unwrap();
+			write_diff_text(file, report);
+		}
+	}
+	// flushed right away so the file can be followed with tail -f
+	file.flush().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::BTreeMap;
+
+	fn sign(x: i32, text: &str) -> SignRecord {
+		SignRecord { dimension: Dimension::Overworld, x, y: 64, z: 0, lines: vec![text.to_string()], back_lines: Vec::new(), color: None, back_color: None, waxed: false, kind: None, rotation: None, facing: None, last_modified: None, raw_text: BTreeMap::new(), provenance: None }
+	}
+
+	#[test]
+	fn regions_are_compared_with_what_they_held_before() {
+		let key = (Dimension::Overworld, 0, 0);
+		let mut records = RegionRecords::new();
+		records.insert(key, (vec![sign(1, "old")], Vec::new()));
+
+		let report = region_changes(&mut records, key, vec![sign(1, "new"), sign(2, "added")], Vec::new());
+		let changes: Vec<Change> = report.signs.iter().map(|sign| sign.change).collect();
+		assert_eq!(changes, vec![Change::Changed, Change::Added]);
+
+		// saving the region again without changes reports nothing
+		let report = region_changes(&mut records, key, vec![sign(1, "new"), sign(2, "added")], Vec::new());
+		assert!(report.signs.is_empty());
+	}
+}
@@ -0,0 +1,63 @@
+use regex::Regex;
+use crate::types::*;
+
+// finds coordinates players wrote on signs like "x: 100 y: 64 z: -200", "100 64 -200" or "x 100 z -200"
+pub struct WaypointFinder {
+	xyz: Regex,
+	xz: Regex,
+}
+
+impl WaypointFinder {
+	pub fn new() -> WaypointFinder {
+		WaypointFinder {
+			xyz: Regex::new(r"(?i)(?:\bx\s*[:=]?\s*)?(?P<x>-?\d+)[\s,/]+(?:y\s*[:=]?\s*)?(?P<y>-?\d+)[\s,/]+(?:z\s*[:=]?\s*)?(?P<z>-?\d+)").expect("invalid regex"),
+			xz: Regex::new(r"(?i)\bx\s*[:=]?\s*(?P<x>-?\d+)[\s,/]+z\s*[:=]?\s*(?P<z>-?\d+)").expect("invalid regex"),
+		}
+	}
+
+	// get the waypoints mentioned on a sign
+	// the lines are joined since coordinates are often split over several lines
+	pub fn find(&self, sign: &SignRecord) -> Vec<Waypoint> {
+		let text = sign.lines.join(" ");
+		let waypoint = |caps: regex::Captures, target_y: Option<i32>| Some(Waypoint {
+			dimension: sign.dimension,
+			x: sign.x,
+			y: sign.y,
+			z: sign.z,
+			target_x: caps["x"].parse().ok()?,
+			target_y,
+			target_z: caps["z"].parse().ok()?,
+		});
+
+		let waypoints: Vec<Waypoint> = self.xyz.captures_iter(&text)
+			.filter_map(|caps| {
+				let target_y = caps["y"].parse().ok()?;
+				waypoint(caps, Some(target_y))
+			})
+			.collect();
+		if !waypoints.is_empty() {
+			return waypoints;
+		}
+		// only look for coordinates without a height if there are none with one
+		self.xz.captures_iter(&text).filter_map(|caps| waypoint(caps, None)).collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::BTreeMap;
+
+	fn targets(lines: &[&str]) -> Vec<(i32, Option<i32>, i32)> {
+		let sign = SignRecord { dimension: Dimension::Overworld, x: 0, y: 0, z: 0, lines: lines.iter().map(|line| line.to_string()).collect(), back_lines: Vec::new(), color: None, back_color: None, waxed: false, kind: None, rotation: None, facing: None, last_modified: None, raw_text: BTreeMap::new(), provenance: None };
+		WaypointFinder::new().find(&sign).into_iter().map(|waypoint| (waypoint.target_x, waypoint.target_y, waypoint.target_z)).collect()
+	}
+
+	#[test]
+	fn coordinates_are_found_in_common_formats() {
+		assert_eq!(targets(&["base", "x: 100 y: 64 z: -200"]), vec![(100, Some(64), -200)]);
+		assert_eq!(targets(&["12 64 -7"]), vec![(12, Some(64), -7)]);
+		assert_eq!(targets(&["x=5,", "z=-3"]), vec![(5, None, -3)]);
+		assert_eq!(targets(&["shop", "open 24/7"]), vec![]);
+	}
+}